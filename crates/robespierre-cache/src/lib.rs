@@ -28,7 +28,7 @@ pub struct CacheConfig {
 
 impl CacheConfig {
     pub fn messages(self, messages: usize) -> CacheConfig {
-        Self { messages, ..self }
+        Self { messages }
     }
 }
 
@@ -502,6 +502,7 @@ impl CommitToCache for ServerToClientEvent {
             ServerToClientEvent::Error { .. } => {}
             ServerToClientEvent::Authenticated => {}
             ServerToClientEvent::Pong { .. } => {}
+            ServerToClientEvent::Unknown { .. } => {}
             ServerToClientEvent::Ready { event } => {
                 for user in event.users.iter() {
                     user.commit_to_cache_ref(cache).await;
@@ -9,19 +9,45 @@ pub extern crate robespierre_events;
 
 use robespierre_cache::{Cache, HasCache};
 use robespierre_events::EventsError;
-use robespierre_http::{HasHttp, Http, HttpAuthentication, HttpError};
+use robespierre_http::{ApiError, HasHttp, Http, HttpAuthentication, HttpError, StatusCode};
 
 pub mod model;
 
+#[cfg(test)]
+mod test_utils;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("http error")]
+    #[error("http error: {0}")]
     Http(#[from] HttpError),
     #[cfg(feature = "events")]
     #[error("events error")]
     Events(#[from] EventsError),
 }
 
+impl Error {
+    /// Gets the error the api responded with, if this error came from one.
+    ///
+    /// Useful to tell apart errors like [`ApiError::MissingPermission`] and
+    /// [`ApiError::UnknownChannel`].
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Http(e) => e.api_error(),
+            #[cfg(feature = "events")]
+            Self::Events(_) => None,
+        }
+    }
+
+    /// Gets the status code of the http response this error came from, if any.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Http(e) => e.status(),
+            #[cfg(feature = "events")]
+            Self::Events(_) => None,
+        }
+    }
+}
+
 pub type Result<T = ()> = std::result::Result<T, Error>;
 
 #[cfg(feature = "cache")]
@@ -1,19 +1,33 @@
+use futures::stream::BoxStream;
 #[cfg(feature = "cache")]
 use robespierre_cache::CommitToCache;
-use robespierre_http::HasHttp;
+use robespierre_http::{messaging::FetchMessagesResult, HasHttp};
 use robespierre_models::{
     autumn::AttachmentId,
-    channels::{Channel, Message, ReplyData},
-    id::{ChannelId, MemberId, ServerId, UserId},
-    servers::{Member, Server},
+    channels::{
+        Channel, GroupChannel, Message, MessageFilterSortDirection, MessageSearchOptions, ReplyData,
+    },
+    id::{ChannelId, MemberId, MessageId, RoleId, ServerId, UserId},
+    servers::{
+        Category, Member, PermissionTuple, Role, RoleEditPatch, Server, ServerEditPatch,
+        SystemMessageChannels,
+    },
     users::User,
 };
 
 use crate::{CacheHttp, Result};
 
-use self::user_opt_member::UserOptMember;
+use self::{
+    messages_stream::MessagesStreamOptions,
+    purge::{PurgeFilter, PurgeReport},
+    server_edit::{EditCategories, EditSystemMessages},
+    user_opt_member::UserOptMember,
+};
 
 pub mod mention;
+pub mod messages_stream;
+pub mod purge;
+pub mod server_edit;
 pub mod user_opt_member;
 
 pub trait IntoString: Into<String> + Send + Sync {}
@@ -67,7 +81,7 @@ impl CreateMessage {
     }
 
     pub fn attachments(&mut self, attachments: Vec<AttachmentId>) -> &mut Self {
-        self.attachments.extend(attachments.into_iter());
+        self.attachments.extend(attachments);
         self
     }
 
@@ -77,7 +91,7 @@ impl CreateMessage {
     }
 
     pub fn replies(&mut self, replies: Vec<ReplyData>) -> &mut Self {
-        self.replies.extend(replies.into_iter());
+        self.replies.extend(replies);
         self
     }
 
@@ -183,6 +197,39 @@ pub trait ChannelIdExt {
     async fn send_message<F>(&self, ctx: &impl HasHttp, message: F) -> Result<Message>
     where
         F: for<'a> FnOnce(&'a mut CreateMessage) -> &'a CreateMessage + Send;
+
+    async fn search(
+        &self,
+        ctx: &impl HasHttp,
+        query: &str,
+        options: MessageSearchOptions,
+    ) -> Result<FetchMessagesResult>;
+
+    /// Walks through the history of the channel, fetching the messages as they are needed.
+    ///
+    /// See [`MessagesStreamOptions::new`] for `direction` and `start`.
+    fn messages_stream<'a, C: CacheHttp + Sync>(
+        &self,
+        ctx: &'a C,
+        direction: MessageFilterSortDirection,
+        start: Option<MessageId>,
+    ) -> BoxStream<'a, Result<Message>> {
+        self.messages_stream_with_options(ctx, MessagesStreamOptions::new(direction, start))
+    }
+
+    /// Like [`Self::messages_stream`], but allows including the users
+    /// and committing everything to the cache.
+    fn messages_stream_with_options<'a, C: CacheHttp + Sync>(
+        &self,
+        ctx: &'a C,
+        options: MessagesStreamOptions,
+    ) -> BoxStream<'a, Result<Message>>;
+
+    /// Deletes the messages matching `filter`, walking the history from the newest message.
+    ///
+    /// Errors are collected in the [`PurgeReport`], together with the messages
+    /// that were deleted before them.
+    async fn purge<C: CacheHttp + Sync>(&self, ctx: &C, filter: PurgeFilter) -> PurgeReport;
 }
 
 #[async_trait::async_trait]
@@ -229,6 +276,169 @@ impl ChannelIdExt for ChannelId {
             )
             .await?)
     }
+
+    async fn search(
+        &self,
+        http: &impl HasHttp,
+        query: &str,
+        options: MessageSearchOptions,
+    ) -> Result<FetchMessagesResult> {
+        Ok(http
+            .get_http()
+            .search_messages(*self, query, options)
+            .await?)
+    }
+
+    fn messages_stream_with_options<'a, C: CacheHttp + Sync>(
+        &self,
+        ctx: &'a C,
+        options: MessagesStreamOptions,
+    ) -> BoxStream<'a, Result<Message>> {
+        messages_stream::messages_stream(*self, ctx, options)
+    }
+
+    async fn purge<C: CacheHttp + Sync>(&self, ctx: &C, filter: PurgeFilter) -> PurgeReport {
+        purge::purge(*self, ctx, filter).await
+    }
+}
+
+#[async_trait::async_trait]
+pub trait GroupExt {
+    async fn members(&self, ctx: &impl HasHttp) -> Result<Vec<User>>;
+    async fn add_user(&self, ctx: &impl HasHttp, user: UserId) -> Result;
+    async fn remove_user(&self, ctx: &impl HasHttp, user: UserId) -> Result;
+    async fn transfer_ownership(&self, ctx: &impl HasHttp, owner: UserId) -> Result;
+    /// Leaves the group.
+    async fn leave(&self, ctx: &impl HasHttp) -> Result;
+}
+
+#[async_trait::async_trait]
+impl GroupExt for GroupChannel {
+    async fn members(&self, http: &impl HasHttp) -> Result<Vec<User>> {
+        Ok(http.get_http().fetch_group_members(self.id).await?)
+    }
+
+    async fn add_user(&self, http: &impl HasHttp, user: UserId) -> Result {
+        Ok(http.get_http().add_group_member(self.id, user).await?)
+    }
+
+    async fn remove_user(&self, http: &impl HasHttp, user: UserId) -> Result {
+        Ok(http.get_http().remove_group_member(self.id, user).await?)
+    }
+
+    async fn transfer_ownership(&self, http: &impl HasHttp, owner: UserId) -> Result {
+        Ok(http
+            .get_http()
+            .transfer_group_ownership(self.id, owner)
+            .await?)
+    }
+
+    async fn leave(&self, http: &impl HasHttp) -> Result {
+        Ok(http.get_http().close_channel(self.id).await?)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait RoleExt {
+    async fn role(&self, ctx: &impl CacheHttp, server: ServerId) -> Result<Option<Role>>;
+    async fn edit(&self, ctx: &impl HasHttp, server: ServerId, patch: RoleEditPatch) -> Result;
+    async fn set_permissions(
+        &self,
+        ctx: &impl HasHttp,
+        server: ServerId,
+        permissions: PermissionTuple,
+    ) -> Result;
+    async fn delete(&self, ctx: &impl HasHttp, server: ServerId) -> Result;
+}
+
+#[async_trait::async_trait]
+impl RoleExt for RoleId {
+    async fn role(&self, ctx: &impl CacheHttp, server: ServerId) -> Result<Option<Role>> {
+        let server = server.server(ctx).await?;
+
+        Ok(server
+            .roles
+            .as_ref()
+            .and_then(|roles| roles.get(self))
+            .cloned())
+    }
+
+    async fn edit(&self, http: &impl HasHttp, server: ServerId, patch: RoleEditPatch) -> Result {
+        Ok(http.get_http().edit_role(server, *self, patch).await?)
+    }
+
+    async fn set_permissions(
+        &self,
+        http: &impl HasHttp,
+        server: ServerId,
+        permissions: PermissionTuple,
+    ) -> Result {
+        Ok(http
+            .get_http()
+            .set_role_permissions(server, *self, permissions)
+            .await?)
+    }
+
+    async fn delete(&self, http: &impl HasHttp, server: ServerId) -> Result {
+        Ok(http.get_http().delete_role(server, *self).await?)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait ServerExt {
+    /// Edits the categories of the server, starting from the ones in `self`,
+    /// and returns the new list.
+    async fn edit_categories<F>(&self, ctx: &impl HasHttp, edit: F) -> Result<Vec<Category>>
+    where
+        F: for<'a> FnOnce(&'a mut EditCategories) -> &'a mut EditCategories + Send;
+
+    /// Edits the channels system messages are sent in, starting from the
+    /// ones in `self`, and returns the new ones.
+    async fn edit_system_messages<F>(
+        &self,
+        ctx: &impl HasHttp,
+        edit: F,
+    ) -> Result<SystemMessageChannels>
+    where
+        F: for<'a> FnOnce(&'a mut EditSystemMessages) -> &'a mut EditSystemMessages + Send;
+}
+
+#[async_trait::async_trait]
+impl ServerExt for Server {
+    async fn edit_categories<F>(&self, http: &impl HasHttp, edit: F) -> Result<Vec<Category>>
+    where
+        F: for<'a> FnOnce(&'a mut EditCategories) -> &'a mut EditCategories + Send,
+    {
+        let mut categories = EditCategories::new(self.categories.clone());
+        edit(&mut categories);
+        let categories = categories.into_categories();
+
+        let mut patch = ServerEditPatch::default();
+        patch.categories = Some(categories.clone());
+        http.get_http().edit_server(self.id, patch).await?;
+
+        Ok(categories)
+    }
+
+    async fn edit_system_messages<F>(
+        &self,
+        http: &impl HasHttp,
+        edit: F,
+    ) -> Result<SystemMessageChannels>
+    where
+        F: for<'a> FnOnce(&'a mut EditSystemMessages) -> &'a mut EditSystemMessages + Send,
+    {
+        let mut channels =
+            EditSystemMessages::new(self.system_messages.clone().unwrap_or_default());
+        edit(&mut channels);
+        let channels = channels.into_channels();
+
+        let mut patch = ServerEditPatch::default();
+        patch.system_messages = Some(channels.clone());
+        http.get_http().edit_server(self.id, patch).await?;
+
+        Ok(channels)
+    }
 }
 
 #[async_trait::async_trait]
@@ -0,0 +1,194 @@
+//! Contains [`MessagesStreamOptions`], to walk through the history of a channel
+//! with [`super::ChannelIdExt::messages_stream`].
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use robespierre_models::{
+    channels::{Message, MessageFilter, MessageFilterSortDirection},
+    id::{ChannelId, MessageId},
+};
+
+use super::CommitToCache;
+use crate::{CacheHttp, Result};
+
+/// The maximum number of messages the api returns at once.
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Options for walking through the history of a channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MessagesStreamOptions {
+    direction: MessageFilterSortDirection,
+    start: Option<MessageId>,
+    page_size: usize,
+    include_users: bool,
+    commit_to_cache: bool,
+}
+
+impl MessagesStreamOptions {
+    /// Walks through the history in `direction`, starting after the `start` message
+    /// (not included), or from the newest / oldest message if `None`.
+    ///
+    /// With [`MessageFilterSortDirection::Latest`], messages are returned from the newest
+    /// to the oldest, and with [`MessageFilterSortDirection::Oldest`], from the oldest to the newest.
+    pub fn new(direction: MessageFilterSortDirection, start: Option<MessageId>) -> Self {
+        Self {
+            direction,
+            start,
+            page_size: MAX_PAGE_SIZE,
+            include_users: false,
+            commit_to_cache: false,
+        }
+    }
+
+    /// How many messages to fetch with a single request, at most 100.
+    pub fn page_size(self, page_size: usize) -> Self {
+        Self {
+            page_size: page_size.clamp(1, MAX_PAGE_SIZE),
+            ..self
+        }
+    }
+
+    /// Also fetch the authors of the messages, and their members if the channel is
+    /// in a server; they are committed to the cache together with the messages.
+    pub fn include_users(self, include_users: bool) -> Self {
+        Self {
+            include_users,
+            ..self
+        }
+    }
+
+    /// Commit the messages (and users / members, if included) to the cache.
+    pub fn commit_to_cache(self, commit_to_cache: bool) -> Self {
+        Self {
+            commit_to_cache,
+            ..self
+        }
+    }
+}
+
+struct State {
+    cursor: Option<MessageId>,
+    buffer: VecDeque<Message>,
+    done: bool,
+}
+
+pub(crate) fn messages_stream<C: CacheHttp + Sync>(
+    channel: ChannelId,
+    ctx: &C,
+    options: MessagesStreamOptions,
+) -> BoxStream<'_, Result<Message>> {
+    let state = State {
+        cursor: options.start,
+        buffer: VecDeque::new(),
+        done: false,
+    };
+
+    stream::try_unfold(state, move |mut state| async move {
+        loop {
+            if let Some(message) = state.buffer.pop_front() {
+                return Ok(Some((message, state)));
+            }
+            if state.done {
+                return Ok(None);
+            }
+
+            let (before, after) = match options.direction {
+                MessageFilterSortDirection::Latest => (state.cursor, None),
+                MessageFilterSortDirection::Oldest => (None, state.cursor),
+            };
+            let filter = MessageFilter {
+                limit: Some(options.page_size),
+                before,
+                after,
+                sort: options.direction,
+                nearby: None,
+                include_users: Some(options.include_users),
+            };
+            let page = ctx.http().fetch_messages(channel, filter).await?;
+
+            state.done = page.messages.len() < options.page_size;
+            if let Some(last) = page.messages.last() {
+                state.cursor = Some(last.id);
+            }
+
+            if options.commit_to_cache {
+                for user in page.users {
+                    user.commit_to_cache(ctx).await;
+                }
+                for member in page.members {
+                    member.commit_to_cache(ctx).await;
+                }
+                for message in page.messages {
+                    state.buffer.push_back(message.commit_to_cache(ctx).await);
+                }
+            } else {
+                state.buffer.extend(page.messages);
+            }
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::{
+        model::ChannelIdExt,
+        test_utils::{message_id, MockChannel, MockCtx},
+    };
+
+    async fn walk(
+        history: &[MessageId],
+        options: MessagesStreamOptions,
+    ) -> (Vec<MessageId>, Arc<MockChannel>) {
+        let transport = Arc::new(MockChannel {
+            history: history.to_vec(),
+            ..Default::default()
+        });
+        let ctx = MockCtx::new(Arc::clone(&transport));
+
+        let messages = MockCtx::channel()
+            .messages_stream_with_options(&ctx, options)
+            .map_ok(|message| message.id)
+            .try_collect()
+            .await
+            .unwrap();
+
+        (messages, transport)
+    }
+
+    #[tokio::test]
+    async fn walks_pages_until_a_short_one() {
+        let history = (0..7)
+            .map(|n| message_id(1_600_000_000_000, n))
+            .collect::<Vec<_>>();
+
+        let options = MessagesStreamOptions::new(MessageFilterSortDirection::Latest, None);
+        let (messages, transport) = walk(&history, options.page_size(3)).await;
+        assert_eq!(messages, history.iter().rev().copied().collect::<Vec<_>>());
+
+        let fetches = transport.fetches();
+        assert_eq!(fetches.len(), 3);
+        assert_eq!(fetches[0].get("before"), None);
+        assert_eq!(fetches[1]["before"], history[4].to_string());
+        assert_eq!(fetches[2]["before"], history[1].to_string());
+        assert!(fetches.iter().all(|query| query["limit"] == "3"));
+        assert!(fetches.iter().all(|query| query["sort"] == "Latest"));
+
+        let options =
+            MessagesStreamOptions::new(MessageFilterSortDirection::Oldest, Some(history[1]));
+        let (messages, transport) = walk(&history, options.page_size(3)).await;
+        assert_eq!(messages, history[2..]);
+
+        let fetches = transport.fetches();
+        assert_eq!(fetches.len(), 2);
+        assert_eq!(fetches[0]["after"], history[1].to_string());
+        assert_eq!(fetches[1]["after"], history[4].to_string());
+        assert!(fetches.iter().all(|query| query["sort"] == "Oldest"));
+    }
+}
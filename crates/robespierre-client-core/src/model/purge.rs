@@ -0,0 +1,338 @@
+//! Contains [`PurgeFilter`], to delete messages in bulk
+//! with [`super::ChannelIdExt::purge`].
+
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{future, StreamExt, TryStreamExt};
+use regex::Regex;
+use robespierre_models::{
+    channels::{Message, MessageContent, MessageFilterSortDirection},
+    id::{ChannelId, MessageId, UserId},
+};
+
+use super::messages_stream::{self, MessagesStreamOptions};
+use crate::{CacheHttp, Error};
+
+/// Which messages to delete when purging a channel.
+///
+/// Messages have to match all the conditions to be deleted.
+#[derive(Debug, Clone)]
+pub struct PurgeFilter {
+    author: Option<UserId>,
+    max_age: Option<Duration>,
+    min_age: Option<Duration>,
+    content: Option<Regex>,
+    has_attachments: Option<bool>,
+    limit: Option<usize>,
+    concurrency: usize,
+}
+
+impl Default for PurgeFilter {
+    fn default() -> Self {
+        Self {
+            author: None,
+            max_age: None,
+            min_age: None,
+            content: None,
+            has_attachments: None,
+            limit: None,
+            concurrency: 4,
+        }
+    }
+}
+
+impl PurgeFilter {
+    /// A filter matching all the messages in the channel.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only delete messages sent by `author`.
+    pub fn author(self, author: UserId) -> Self {
+        Self {
+            author: Some(author),
+            ..self
+        }
+    }
+
+    /// Only delete messages sent in the last `max_age`.
+    ///
+    /// The history is only walked until the first message older than that.
+    pub fn max_age(self, max_age: Duration) -> Self {
+        Self {
+            max_age: Some(max_age),
+            ..self
+        }
+    }
+
+    /// Only delete messages sent at least `min_age` ago.
+    pub fn min_age(self, min_age: Duration) -> Self {
+        Self {
+            min_age: Some(min_age),
+            ..self
+        }
+    }
+
+    /// Only delete messages with content matching `content`.
+    pub fn content(self, content: Regex) -> Self {
+        Self {
+            content: Some(content),
+            ..self
+        }
+    }
+
+    /// Only delete messages with (if `true`) or without (if `false`) attachments.
+    pub fn has_attachments(self, has_attachments: bool) -> Self {
+        Self {
+            has_attachments: Some(has_attachments),
+            ..self
+        }
+    }
+
+    /// Delete at most `limit` messages, the most recent ones.
+    pub fn limit(self, limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..self
+        }
+    }
+
+    /// How many messages to delete at the same time; defaults to 4.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    fn age(message: &Message, now: Duration) -> Duration {
+        let sent = Duration::from_millis(message.id.datetime().timestamp_millis().max(0) as u64);
+        now.saturating_sub(sent)
+    }
+
+    fn too_old(&self, message: &Message, now: Duration) -> bool {
+        matches!(self.max_age, Some(max_age) if Self::age(message, now) > max_age)
+    }
+
+    fn matches(&self, message: &Message, now: Duration) -> bool {
+        if matches!(self.author, Some(author) if author != message.author) {
+            return false;
+        }
+        if matches!(self.min_age, Some(min_age) if Self::age(message, now) < min_age) {
+            return false;
+        }
+        if let Some(content) = &self.content {
+            match &message.content {
+                MessageContent::Content(text) if content.is_match(text) => {}
+                _ => return false,
+            }
+        }
+        if matches!(self.has_attachments, Some(has) if has == message.attachments.is_empty()) {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// The messages deleted by [`super::ChannelIdExt::purge`].
+#[derive(Debug, Default)]
+pub struct PurgeReport {
+    /// The messages that were looked at.
+    pub scanned: usize,
+    /// The messages that were deleted.
+    pub deleted: Vec<MessageId>,
+    /// The messages that matched the filter, but couldn't be deleted.
+    pub failed: Vec<(MessageId, Error)>,
+    /// The error fetching the history failed with, if it did.
+    ///
+    /// The purge stops there, but the messages deleted
+    /// until then are still in [`Self::deleted`].
+    pub fetch_error: Option<Error>,
+}
+
+pub(crate) async fn purge<C: CacheHttp + Sync>(
+    channel: ChannelId,
+    ctx: &C,
+    filter: PurgeFilter,
+) -> PurgeReport {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let report = Mutex::new(PurgeReport::default());
+
+    let options = MessagesStreamOptions::new(MessageFilterSortDirection::Latest, None);
+    messages_stream::messages_stream(channel, ctx, options)
+        .try_take_while(|message| future::ready(Ok(!filter.too_old(message, now))))
+        .try_filter(|message| {
+            report.lock().unwrap().scanned += 1;
+            future::ready(filter.matches(message, now))
+        })
+        .scan((), |(), result| {
+            future::ready(match result {
+                Ok(message) => Some(message),
+                Err(e) => {
+                    report.lock().unwrap().fetch_error = Some(e);
+                    None
+                }
+            })
+        })
+        .take(filter.limit.unwrap_or(usize::MAX))
+        // the deletes still go through the rate limiter, this
+        // only bounds how many are queued on it at once
+        .for_each_concurrent(filter.concurrency, |message| {
+            let report = &report;
+            async move {
+                let result = ctx.http().delete_message(channel, message.id).await;
+
+                let mut report = report.lock().unwrap();
+                match result {
+                    Ok(()) => report.deleted.push(message.id),
+                    Err(e) => report.failed.push((message.id, e.into())),
+                }
+            }
+        })
+        .await;
+
+    report.into_inner().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        model::ChannelIdExt,
+        test_utils::{message_id, MockChannel, MockCtx},
+    };
+
+    fn message(author: &str, content: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "_id": "01FE9XXP4NWJ6S2D4N7WX6W3AJ",
+            "channel": "01FE9XVK2GXQXHZ5BCZBD6PA8P",
+            "author": author,
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn matches_all_conditions() {
+        let author = "01FE9XNFEDH6NSPKRQY5SVXYRP";
+        let other = "01FE9XVK2GXQXHZ5BCZBD6PA8P";
+        let message = message(author, "buy cheap stuff");
+        let now = Duration::from_millis(message.id.datetime().timestamp_millis() as u64)
+            + Duration::from_secs(60);
+
+        assert!(PurgeFilter::new().matches(&message, now));
+        assert!(PurgeFilter::new()
+            .author(author.parse().unwrap())
+            .content(Regex::new("cheap").unwrap())
+            .has_attachments(false)
+            .min_age(Duration::from_secs(30))
+            .matches(&message, now));
+
+        assert!(!PurgeFilter::new()
+            .author(other.parse().unwrap())
+            .matches(&message, now));
+        assert!(!PurgeFilter::new()
+            .content(Regex::new("^hello").unwrap())
+            .matches(&message, now));
+        assert!(!PurgeFilter::new()
+            .has_attachments(true)
+            .matches(&message, now));
+        assert!(!PurgeFilter::new()
+            .min_age(Duration::from_secs(120))
+            .matches(&message, now));
+
+        assert!(PurgeFilter::new()
+            .max_age(Duration::from_secs(30))
+            .too_old(&message, now));
+        assert!(!PurgeFilter::new()
+            .max_age(Duration::from_secs(120))
+            .too_old(&message, now));
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    async fn purge(transport: &Arc<MockChannel>, filter: PurgeFilter) -> PurgeReport {
+        let ctx = MockCtx::new(Arc::clone(transport));
+        MockCtx::channel().purge(&ctx, filter).await
+    }
+
+    #[tokio::test]
+    async fn deletes_at_most_limit_messages() {
+        let history = (0..10)
+            .map(|n| message_id(now_millis(), n))
+            .collect::<Vec<_>>();
+        let transport = Arc::new(MockChannel {
+            history: history.clone(),
+            ..Default::default()
+        });
+
+        let mut report = purge(&transport, PurgeFilter::new().limit(3)).await;
+        report.deleted.sort();
+        assert_eq!(report.deleted, history[7..]);
+        assert!(report.failed.is_empty());
+        assert!(report.fetch_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_first_message_older_than_max_age() {
+        let hour = 60 * 60 * 1000;
+        let history = (0..5)
+            .map(|n| message_id(now_millis() - 2 * hour, n))
+            .chain((5..8).map(|n| message_id(now_millis(), n)))
+            .collect::<Vec<_>>();
+        let transport = Arc::new(MockChannel {
+            history: history.clone(),
+            ..Default::default()
+        });
+
+        let filter = PurgeFilter::new().max_age(Duration::from_secs(60 * 60));
+        let mut report = purge(&transport, filter).await;
+        report.deleted.sort();
+        assert_eq!(report.scanned, 3);
+        assert_eq!(report.deleted, history[5..]);
+    }
+
+    #[tokio::test]
+    async fn reports_failures_with_the_deleted_messages() {
+        let history = (0..150)
+            .map(|n| message_id(now_millis(), n))
+            .collect::<Vec<_>>();
+        let transport = Arc::new(MockChannel {
+            history: history.clone(),
+            undeletable: vec![history[149]],
+            fetch_limit: Some(1),
+            ..Default::default()
+        });
+
+        let report = purge(&transport, PurgeFilter::new()).await;
+        assert_eq!(report.scanned, 100);
+        assert_eq!(report.deleted.len(), 99);
+        assert!(!report.deleted.contains(&history[149]));
+
+        assert_eq!(report.failed.len(), 1);
+        let (message, error) = &report.failed[0];
+        assert_eq!(*message, history[149]);
+        assert!(matches!(
+            error.api_error(),
+            Some(robespierre_http::ApiError::MissingPermission { .. })
+        ));
+
+        assert_eq!(
+            report.fetch_error.as_ref().and_then(Error::status),
+            Some(robespierre_http::StatusCode::INTERNAL_SERVER_ERROR)
+        );
+    }
+}
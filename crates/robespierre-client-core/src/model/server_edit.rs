@@ -0,0 +1,188 @@
+//! Contains [`EditCategories`] and [`EditSystemMessages`], to change the layout
+//! of a server with [`super::ServerExt`].
+
+use robespierre_models::{
+    id::{CategoryId, ChannelId},
+    servers::{Category, SystemMessageChannels},
+};
+
+/// Edits the list of categories of a server, starting from the current one.
+#[derive(Debug, Clone, Default)]
+pub struct EditCategories {
+    categories: Vec<Category>,
+}
+
+impl EditCategories {
+    pub fn new(categories: Vec<Category>) -> Self {
+        Self { categories }
+    }
+
+    /// Creates a category at the end of the list, with a new id.
+    pub fn create(&mut self, title: impl Into<String>, channels: Vec<ChannelId>) -> &mut Self {
+        let id = rusty_ulid::generate_ulid_string()
+            .parse()
+            .expect("ulids are valid category ids");
+
+        self.create_with_id(id, title, channels)
+    }
+
+    /// Creates a category at the end of the list.
+    ///
+    /// The channels are moved out of the categories they were in.
+    pub fn create_with_id(
+        &mut self,
+        id: CategoryId,
+        title: impl Into<String>,
+        channels: Vec<ChannelId>,
+    ) -> &mut Self {
+        for channel in &channels {
+            self.remove_channel(*channel);
+        }
+        self.categories.push(Category {
+            id,
+            title: title.into(),
+            channels,
+        });
+        self
+    }
+
+    /// Deletes a category; its channels are left without a category.
+    pub fn delete(&mut self, id: CategoryId) -> &mut Self {
+        self.categories.retain(|category| category.id != id);
+        self
+    }
+
+    pub fn rename(&mut self, id: CategoryId, title: impl Into<String>) -> &mut Self {
+        if let Some(category) = self.get_mut(id) {
+            category.title = title.into();
+        }
+        self
+    }
+
+    /// Moves a category to `index` in the list, or to the end if
+    /// `index` is past it.
+    pub fn move_category(&mut self, id: CategoryId, index: usize) -> &mut Self {
+        if let Some(position) = self.categories.iter().position(|c| c.id == id) {
+            let category = self.categories.remove(position);
+            let index = index.min(self.categories.len());
+            self.categories.insert(index, category);
+        }
+        self
+    }
+
+    /// Moves a channel at the end of a category, out of the category it was in.
+    pub fn add_channel(&mut self, id: CategoryId, channel: ChannelId) -> &mut Self {
+        if self.get_mut(id).is_some() {
+            self.remove_channel(channel);
+        }
+        if let Some(category) = self.get_mut(id) {
+            category.channels.push(channel);
+        }
+        self
+    }
+
+    /// Removes a channel from the category it is in.
+    pub fn remove_channel(&mut self, channel: ChannelId) -> &mut Self {
+        for category in &mut self.categories {
+            category.channels.retain(|c| *c != channel);
+        }
+        self
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    pub fn into_categories(self) -> Vec<Category> {
+        self.categories
+    }
+
+    fn get_mut(&mut self, id: CategoryId) -> Option<&mut Category> {
+        self.categories
+            .iter_mut()
+            .find(|category| category.id == id)
+    }
+}
+
+/// Edits the channels system messages are sent in.
+#[derive(Debug, Clone, Default)]
+pub struct EditSystemMessages {
+    channels: SystemMessageChannels,
+}
+
+impl EditSystemMessages {
+    pub fn new(channels: SystemMessageChannels) -> Self {
+        Self { channels }
+    }
+
+    /// Where to announce users joining, or `None` to not announce them.
+    pub fn user_joined(&mut self, channel: Option<ChannelId>) -> &mut Self {
+        self.channels.user_joined = channel;
+        self
+    }
+
+    /// Where to announce users leaving, or `None` to not announce them.
+    pub fn user_left(&mut self, channel: Option<ChannelId>) -> &mut Self {
+        self.channels.user_left = channel;
+        self
+    }
+
+    /// Where to announce users being kicked, or `None` to not announce them.
+    pub fn user_kicked(&mut self, channel: Option<ChannelId>) -> &mut Self {
+        self.channels.user_kicked = channel;
+        self
+    }
+
+    /// Where to announce users being banned, or `None` to not announce them.
+    pub fn user_banned(&mut self, channel: Option<ChannelId>) -> &mut Self {
+        self.channels.user_banned = channel;
+        self
+    }
+
+    /// Sends all the system messages to `channel`.
+    pub fn all(&mut self, channel: Option<ChannelId>) -> &mut Self {
+        self.user_joined(channel)
+            .user_left(channel)
+            .user_kicked(channel)
+            .user_banned(channel)
+    }
+
+    pub fn into_channels(self) -> SystemMessageChannels {
+        self.channels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_are_in_one_category() {
+        let info: CategoryId = "info".parse().unwrap();
+        let chat: CategoryId = "chat".parse().unwrap();
+        let rules: ChannelId = "01FE9XVK2GXQXHZ5BCZBD6PA8P".parse().unwrap();
+        let general: ChannelId = "01FE9XXP4NWJ6S2D4N7WX6W3AJ".parse().unwrap();
+
+        let mut edit = EditCategories::default();
+        edit.create_with_id(info, "Info", vec![rules, general])
+            .create_with_id(chat, "Chat", vec![general])
+            .move_category(chat, 0);
+
+        let categories = edit.categories();
+        assert_eq!(categories[0].id, chat);
+        assert_eq!(categories[0].channels, vec![general]);
+        assert_eq!(categories[1].channels, vec![rules]);
+
+        edit.add_channel(chat, rules)
+            .delete(info)
+            .rename(chat, "Everything");
+        assert_eq!(
+            edit.into_categories(),
+            vec![Category {
+                id: chat,
+                title: "Everything".to_string(),
+                channels: vec![general, rules],
+            }]
+        );
+    }
+}
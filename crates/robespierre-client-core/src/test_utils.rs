@@ -0,0 +1,148 @@
+//! A fake channel served by a mock [`Transport`], to test the helpers that
+//! walk through the history of a channel.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use robespierre_cache::{Cache, HasCache};
+use robespierre_http::{
+    reqwest::{Method, Request, Response, Url},
+    transport::Transport,
+    HasHttp, Http, HttpAuthentication, RetryPolicy,
+};
+use robespierre_models::id::{ChannelId, MessageId};
+
+pub(crate) const CHANNEL: &str = "01FE9XVK2GXQXHZ5BCZBD6PA8P";
+pub(crate) const AUTHOR: &str = "01FE9XNFEDH6NSPKRQY5SVXYRP";
+
+/// A message id sent at `timestamp` (in milliseconds), unique by `n`.
+pub(crate) fn message_id(timestamp: u64, n: u64) -> MessageId {
+    rusty_ulid::Ulid::from(((timestamp as u128) << 80) | n as u128)
+        .to_string()
+        .parse()
+        .unwrap()
+}
+
+/// Serves the history of [`CHANNEL`], and accepts deleting its messages.
+#[derive(Default)]
+pub(crate) struct MockChannel {
+    /// The messages in the channel, from the oldest to the newest.
+    pub(crate) history: Vec<MessageId>,
+    /// Deleting these messages fails.
+    pub(crate) undeletable: Vec<MessageId>,
+    /// Fetching the history fails after this many fetches.
+    pub(crate) fetch_limit: Option<usize>,
+    /// The requests that went through, by method and url.
+    pub(crate) requests: Mutex<Vec<(Method, Url)>>,
+}
+
+impl MockChannel {
+    pub(crate) fn fetches(&self) -> Vec<HashMap<String, String>> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(method, _)| method == Method::GET)
+            .map(|(_, url)| url.query_pairs().into_owned().collect())
+            .collect()
+    }
+
+    fn fetch(&self, query: HashMap<String, String>) -> (u16, String) {
+        if matches!(self.fetch_limit, Some(limit) if self.fetches().len() > limit) {
+            return (500, String::new());
+        }
+
+        let id = |key: &str| query.get(key).map(|id| id.parse::<MessageId>().unwrap());
+        let (before, after) = (id("before"), id("after"));
+        let limit = query
+            .get("limit")
+            .map_or(50, |limit| limit.parse().unwrap());
+
+        let messages = self
+            .history
+            .iter()
+            .filter(|id| !matches!(before, Some(before) if **id >= before))
+            .filter(|id| !matches!(after, Some(after) if **id <= after));
+        let messages: Vec<_> = match query.get("sort").map(String::as_str) {
+            Some("Oldest") => messages.take(limit).collect(),
+            _ => messages.rev().take(limit).collect(),
+        };
+
+        let messages = messages
+            .into_iter()
+            .map(|id| {
+                serde_json::json!({
+                    "_id": id,
+                    "channel": CHANNEL,
+                    "author": AUTHOR,
+                    "content": "hello",
+                })
+            })
+            .collect::<Vec<_>>();
+
+        (200, serde_json::to_string(&messages).unwrap())
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for MockChannel {
+    async fn execute(&self, request: Request) -> robespierre_http::Result<Response> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request.method().clone(), request.url().clone()));
+
+        let segments = request.url().path_segments().unwrap().collect::<Vec<_>>();
+        let (status, body) = match (request.method(), segments.as_slice()) {
+            (&Method::GET, ["channels", _, "messages"]) => {
+                self.fetch(request.url().query_pairs().into_owned().collect())
+            }
+            (&Method::DELETE, ["channels", _, "messages", message]) => {
+                if self.undeletable.contains(&message.parse().unwrap()) {
+                    let error = r#"{"type":"MissingPermission","permission":"ManageMessages"}"#;
+                    (403, error.to_string())
+                } else {
+                    (204, String::new())
+                }
+            }
+            _ => (404, r#"{"type":"NotFound"}"#.to_string()),
+        };
+
+        Ok(http::Response::builder()
+            .status(status)
+            .body(body)
+            .unwrap()
+            .into())
+    }
+}
+
+/// A context without a cache, going through a mock transport.
+pub(crate) struct MockCtx(Http);
+
+impl MockCtx {
+    pub(crate) fn new(transport: impl Transport) -> Self {
+        Self(
+            Http::builder()
+                .api_root("https://revolt.test")
+                .transport(transport)
+                .retry_policy(RetryPolicy::none())
+                .build_lazy(HttpAuthentication::BotToken { token: "token" })
+                .unwrap(),
+        )
+    }
+
+    pub(crate) fn channel() -> ChannelId {
+        CHANNEL.parse().unwrap()
+    }
+}
+
+impl HasHttp for MockCtx {
+    fn get_http(&self) -> &Http {
+        &self.0
+    }
+}
+
+impl HasCache for MockCtx {
+    fn get_cache(&self) -> Option<&Cache> {
+        None
+    }
+}
@@ -0,0 +1,132 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    heartbeat::Heartbeat, Authentication, Connection, ConnectionInternal, DispatchStrategy, Result,
+    WireFormat,
+};
+
+/// A builder for [`Connection`], to configure the heartbeat and timeouts.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use robespierre_events::{Authentication, Connection};
+/// # async fn f() -> robespierre_events::Result {
+/// let connection = Connection::builder()
+///     .heartbeat_interval(Duration::from_secs(10))
+///     .heartbeat_timeout(Duration::from_secs(30))
+///     .handshake_timeout(Duration::from_secs(10))
+///     .auth_timeout(Duration::from_secs(10))
+///     .connect(Authentication::Bot { token: "..." })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder {
+    pub(crate) url: String,
+    pub(crate) format: WireFormat,
+    pub(crate) strict: bool,
+    pub(crate) dispatch: DispatchStrategy,
+    pub(crate) heartbeat_interval: Duration,
+    pub(crate) heartbeat_timeout: Option<Duration>,
+    pub(crate) handshake_timeout: Option<Duration>,
+    pub(crate) auth_timeout: Option<Duration>,
+}
+
+impl Default for ConnectionBuilder {
+    fn default() -> Self {
+        Self {
+            url: "wss://ws.revolt.chat".to_string(),
+            format: WireFormat::default(),
+            strict: false,
+            dispatch: DispatchStrategy::default(),
+            heartbeat_interval: Duration::from_secs(15),
+            heartbeat_timeout: None,
+            handshake_timeout: None,
+            auth_timeout: None,
+        }
+    }
+}
+
+impl ConnectionBuilder {
+    /// Creates a new builder, for the official revolt instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the url of the websocket.
+    ///
+    /// Use this if using a self hosted instance of revolt.
+    pub fn url(self, url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            ..self
+        }
+    }
+
+    /// Sets the [`WireFormat`] to negotiate; defaults to json.
+    pub fn format(self, format: WireFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    /// See [`Connection::strict`].
+    pub fn strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    /// Sets how [`Connection::run`] hands the events to the handler;
+    /// defaults to [`DispatchStrategy::Concurrent`].
+    pub fn dispatch(self, dispatch: DispatchStrategy) -> Self {
+        Self { dispatch, ..self }
+    }
+
+    /// Sets how often to ping the server; defaults to 15 seconds.
+    pub fn heartbeat_interval(self, heartbeat_interval: Duration) -> Self {
+        Self {
+            heartbeat_interval,
+            ..self
+        }
+    }
+
+    /// Fails with [`crate::EventsError::HeartbeatTimeout`] when the server didn't
+    /// reply to the pings for longer than `heartbeat_timeout`.
+    ///
+    /// Should be a few times the heartbeat interval.
+    pub fn heartbeat_timeout(self, heartbeat_timeout: Duration) -> Self {
+        Self {
+            heartbeat_timeout: Some(heartbeat_timeout),
+            ..self
+        }
+    }
+
+    /// Sets the timeout for opening the websocket.
+    pub fn handshake_timeout(self, handshake_timeout: Duration) -> Self {
+        Self {
+            handshake_timeout: Some(handshake_timeout),
+            ..self
+        }
+    }
+
+    /// Sets the timeout for the server to reply to the authentication.
+    pub fn auth_timeout(self, auth_timeout: Duration) -> Self {
+        Self {
+            auth_timeout: Some(auth_timeout),
+            ..self
+        }
+    }
+
+    /// Connects to the websocket, and authenticates, returning the socket or an error if it failed.
+    pub async fn connect<'a>(self, auth: impl Into<Authentication<'a>>) -> Result<Connection> {
+        let token = auth.into().token();
+        let heartbeat = Arc::new(Heartbeat::new());
+        let internal = ConnectionInternal::connect(&self, token, Arc::clone(&heartbeat)).await?;
+
+        Ok(Connection {
+            internal,
+            ping_interval: tokio::time::interval(self.heartbeat_interval),
+            token: token.to_string(),
+            heartbeat,
+            options: self,
+        })
+    }
+}
@@ -0,0 +1,300 @@
+//! How [`crate::Connection::run`] hands the events to the handler.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::{
+    channel::oneshot,
+    future::{self, Shared},
+    Future, FutureExt,
+};
+use robespierre_models::{
+    events::ServerToClientEvent,
+    id::{ChannelId, ServerId, UserId},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// How events are handed to the handler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DispatchStrategy {
+    /// Every event is handled in a new task, as soon as it is received,
+    /// so events can be handled in any order, and there is no limit to
+    /// how many are handled at the same time.
+    Concurrent,
+    /// Events are handled one at a time, in the order they were received.
+    ///
+    /// When `max_in_flight` events are waiting to be handled, no more
+    /// events are read from the websocket until one of them is handled.
+    Sequential { max_in_flight: usize },
+    /// Events about the same channel, server or user are handled in the order
+    /// they were received, and events about different ones concurrently.
+    ///
+    /// Events that are about none of them, like `Ready`, wait for all the
+    /// events received before them, and all the events received after
+    /// them wait for them.
+    ///
+    /// When `max_in_flight` events are waiting to be handled, no more
+    /// events are read from the websocket until one of them is handled.
+    OrderedPerKey { max_in_flight: usize },
+}
+
+impl Default for DispatchStrategy {
+    fn default() -> Self {
+        Self::Concurrent
+    }
+}
+
+/// What events are ordered by, with [`DispatchStrategy::OrderedPerKey`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum DispatchKey {
+    Channel(ChannelId),
+    Server(ServerId),
+    User(UserId),
+}
+
+impl DispatchKey {
+    pub(crate) fn of(event: &ServerToClientEvent) -> Option<Self> {
+        use ServerToClientEvent::*;
+
+        let key = match event {
+            Message { message } => Self::Channel(message.channel),
+            MessageUpdate { channel, .. } | MessageDelete { channel, .. } => {
+                Self::Channel(*channel)
+            }
+            ChannelCreate { channel } => Self::Channel(channel.id()),
+            ChannelUpdate { id, .. }
+            | ChannelDelete { id }
+            | ChannelGroupJoin { id, .. }
+            | ChannelGroupLeave { id, .. }
+            | ChannelStartTyping { id, .. }
+            | ChannelStopTyping { id, .. }
+            | ChannelAck { id, .. } => Self::Channel(*id),
+            ServerUpdate { id, .. }
+            | ServerDelete { id }
+            | ServerMemberJoin { id, .. }
+            | ServerMemberLeave { id, .. }
+            | ServerRoleUpdate { id, .. }
+            | ServerRoleDelete { id, .. } => Self::Server(*id),
+            ServerMemberUpdate { id, .. } => Self::Server(id.server),
+            UserUpdate { id, .. } | UserRelationship { id, .. } => Self::User(*id),
+            Error { .. } | Authenticated | Pong { .. } | Ready { .. } | Unknown { .. } => {
+                return None
+            }
+        };
+
+        Some(key)
+    }
+}
+
+/// Tells when the handler is done with an event.
+#[derive(Clone)]
+struct Done {
+    finished: Arc<AtomicBool>,
+    /// Resolves when the handler is done.
+    notified: Shared<oneshot::Receiver<()>>,
+}
+
+impl Done {
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+/// Room for one more event to be handled, from [`Dispatcher::reserve`].
+#[derive(Default)]
+pub(crate) struct Permit {
+    /// Only held until the event is handled.
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+pub(crate) struct Dispatcher {
+    strategy: DispatchStrategy,
+    in_flight: Option<Arc<Semaphore>>,
+    /// The last event dispatched for every key, that wasn't handled yet.
+    pending: HashMap<DispatchKey, Done>,
+    /// The last event dispatched without a key, if it wasn't handled yet.
+    barrier: Option<Done>,
+}
+
+impl Dispatcher {
+    pub(crate) fn new(strategy: DispatchStrategy) -> Self {
+        let in_flight = match strategy {
+            DispatchStrategy::Concurrent => None,
+            DispatchStrategy::Sequential { max_in_flight }
+            | DispatchStrategy::OrderedPerKey { max_in_flight } => {
+                Some(Arc::new(Semaphore::new(max_in_flight.max(1))))
+            }
+        };
+
+        Self {
+            strategy,
+            in_flight,
+            pending: HashMap::new(),
+            barrier: None,
+        }
+    }
+
+    /// Waits until there's room for one more event to be handled, if there is a limit.
+    pub(crate) async fn reserve(&self) -> Permit {
+        match &self.in_flight {
+            Some(in_flight) => Permit {
+                _permit: Some(
+                    Arc::clone(in_flight)
+                        .acquire_owned()
+                        .await
+                        .expect("the semaphore is never closed"),
+                ),
+            },
+            None => Permit::default(),
+        }
+    }
+
+    /// Whether [`Self::reserve`] would have to wait.
+    pub(crate) fn is_full(&self) -> bool {
+        matches!(&self.in_flight, Some(in_flight) if in_flight.available_permits() == 0)
+    }
+
+    /// Spawns `handle`, the handling of an event with the given key, after the events
+    /// it has to wait for, holding on to `permit` until it's done.
+    ///
+    /// Notifications that aren't events, like reconnects, don't have a key either.
+    pub(crate) fn dispatch<F>(&mut self, key: Option<DispatchKey>, handle: F, permit: Permit)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let key = match self.strategy {
+            DispatchStrategy::Concurrent => {
+                tokio::spawn(handle);
+                return;
+            }
+            DispatchStrategy::Sequential { .. } => None,
+            DispatchStrategy::OrderedPerKey { .. } => key,
+        };
+
+        // forget about the events that were handled already
+        self.pending.retain(|_, done| !done.is_finished());
+        if matches!(&self.barrier, Some(done) if done.is_finished()) {
+            self.barrier = None;
+        }
+
+        let wait_for: Vec<_> = match key {
+            Some(key) => self
+                .pending
+                .get(&key)
+                .or(self.barrier.as_ref())
+                .cloned()
+                .into_iter()
+                .collect(),
+            None => self
+                .pending
+                .drain()
+                .map(|(_, done)| done)
+                .chain(self.barrier.take())
+                .collect(),
+        };
+
+        let (done_tx, done_rx) = oneshot::channel();
+        let finished = Arc::new(AtomicBool::new(false));
+        let done = Done {
+            finished: Arc::clone(&finished),
+            notified: done_rx.shared(),
+        };
+        match key {
+            Some(key) => {
+                self.pending.insert(key, done);
+            }
+            None => self.barrier = Some(done),
+        }
+
+        tokio::spawn(async move {
+            // the senders are never used, only dropped, so these always resolve to `Err`
+            future::join_all(wait_for.into_iter().map(|done| done.notified)).await;
+            handle.await;
+
+            finished.store(true, Ordering::Release);
+            drop(permit);
+            drop(done_tx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn typing(channel: &str) -> Option<DispatchKey> {
+        DispatchKey::of(&ServerToClientEvent::ChannelStartTyping {
+            id: channel.parse().unwrap(),
+            user: "01FE9XNFEDH6NSPKRQY5SVXYRP".parse().unwrap(),
+        })
+    }
+
+    #[tokio::test]
+    async fn orders_events_per_key() {
+        let a = typing("01FE9XVK2GXQXHZ5BCZBD6PA8P");
+        let b = typing("01FE9XXP4NWJ6S2D4N7WX6W3AJ");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let handle = |name: &'static str, delay: u64| {
+            let tx = tx.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                tx.send(name).unwrap();
+            }
+        };
+
+        let mut dispatcher = Dispatcher::new(DispatchStrategy::OrderedPerKey { max_in_flight: 8 });
+        for (key, fut) in [
+            (a, handle("a1", 50)),
+            (b, handle("b1", 10)),
+            (a, handle("a2", 0)),
+            (None, handle("barrier", 0)),
+            (b, handle("b2", 0)),
+        ] {
+            let permit = dispatcher.reserve().await;
+            dispatcher.dispatch(key, fut, permit);
+        }
+        drop(tx);
+
+        let mut order = vec![];
+        while let Some(name) = rx.recv().await {
+            order.push(name);
+        }
+        assert_eq!(order, vec!["b1", "a1", "a2", "barrier", "b2"]);
+    }
+
+    #[tokio::test]
+    async fn forgets_handled_events() {
+        let channels = [
+            "01FE9XVK2GXQXHZ5BCZBD6PA8P",
+            "01FE9XXP4NWJ6S2D4N7WX6W3AJ",
+            "01FE9XNFEDH6NSPKRQY5SVXYRP",
+        ];
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let mut dispatcher = Dispatcher::new(DispatchStrategy::OrderedPerKey { max_in_flight: 8 });
+        for channel in channels {
+            let tx = tx.clone();
+            let permit = dispatcher.reserve().await;
+            dispatcher.dispatch(typing(channel), async move { tx.send(()).unwrap() }, permit);
+        }
+        assert_eq!(dispatcher.pending.len(), 3);
+
+        for _ in channels {
+            rx.recv().await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let permit = dispatcher.reserve().await;
+        dispatcher.dispatch(typing(channels[0]), async {}, permit);
+        assert_eq!(dispatcher.pending.len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+//! The formats events can be sent in over the websocket.
+
+use robespierre_models::events::ServerToClientEvent;
+use serde_json::Value;
+
+use crate::EventsError;
+
+/// The format of the events sent over the websocket, negotiated when connecting.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WireFormat {
+    /// Events are sent as json in text frames.
+    Json,
+    /// Events are sent as MessagePack in binary frames, which is smaller and
+    /// faster to decode, especially for large `Ready` events.
+    MessagePack,
+}
+
+impl Default for WireFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl WireFormat {
+    /// Adds the format to the url of the websocket.
+    pub(crate) fn url(self, url: &str) -> String {
+        match self {
+            Self::Json => url.to_string(),
+            Self::MessagePack => {
+                let separator = if url.contains('?') {
+                    "&"
+                } else if has_path(url) {
+                    "?"
+                } else {
+                    // the query can't come right after the host
+                    "/?"
+                };
+                format!("{}{}format=msgpack", url, separator)
+            }
+        }
+    }
+}
+
+fn has_path(url: &str) -> bool {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme.contains('/')
+}
+
+/// Decodes a binary frame, which holds a MessagePack event.
+///
+/// Like [`ServerToClientEvent::from_json`], known events are decoded directly, and
+/// only unknown ones (or all of them, if `strict`) go through an intermediate value.
+// the error is passed on as is by `Connection::get_event`
+#[allow(clippy::result_large_err)]
+pub(crate) fn decode_event(data: &[u8], strict: bool) -> Result<ServerToClientEvent, EventsError> {
+    if !strict {
+        if let Ok(event) = rmp_serde::from_slice::<ServerToClientEvent>(data) {
+            tracing::debug!("[<] {:?}", &event);
+            return Ok(event);
+        }
+    }
+
+    let value = decode_binary(data)?;
+    Ok(ServerToClientEvent::from_value(value, strict)?)
+}
+
+/// Decodes a binary frame into a [`Value`].
+pub(crate) fn decode_binary(data: &[u8]) -> Result<Value, rmp_serde::decode::Error> {
+    let value: Value = rmp_serde::from_slice(data)?;
+    tracing::debug!("[<] {}", &value);
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_format_to_url() {
+        assert_eq!(
+            WireFormat::Json.url("wss://ws.revolt.chat"),
+            "wss://ws.revolt.chat"
+        );
+        assert_eq!(
+            WireFormat::MessagePack.url("wss://ws.revolt.chat"),
+            "wss://ws.revolt.chat/?format=msgpack"
+        );
+        assert_eq!(
+            WireFormat::MessagePack.url("wss://ws.revolt.chat/?version=1"),
+            "wss://ws.revolt.chat/?version=1&format=msgpack"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Keeping track of the pings sent to the server, and of the pongs it replies with.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug)]
+struct HeartbeatState {
+    latency: Option<Duration>,
+    last_pong: Instant,
+}
+
+/// Shared between a [`crate::Connection`] and its [`crate::ConnectionMessanger`]s,
+/// so that the latency can be read while the connection runs.
+#[derive(Debug)]
+pub(crate) struct Heartbeat {
+    /// The pings carry the number of milliseconds since this instant.
+    start: Instant,
+    state: Mutex<HeartbeatState>,
+}
+
+impl Heartbeat {
+    pub(crate) fn new() -> Self {
+        let now = Instant::now();
+
+        Self {
+            start: now,
+            state: Mutex::new(HeartbeatState {
+                latency: None,
+                last_pong: now,
+            }),
+        }
+    }
+
+    /// The data of the next ping.
+    pub(crate) fn ping_data(&self) -> u32 {
+        self.start.elapsed().as_millis() as u32
+    }
+
+    /// Records a pong, for a ping sent with `data`.
+    pub(crate) fn pong(&self, data: u32) {
+        let now = Instant::now();
+        let elapsed = (now - self.start).as_millis() as u32;
+        let latency = Duration::from_millis(elapsed.wrapping_sub(data).into());
+
+        let mut state = self.state.lock().unwrap();
+        state.latency = Some(latency);
+        state.last_pong = now;
+    }
+
+    /// Forgets about the pongs received on a previous connection.
+    pub(crate) fn reset(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.latency = None;
+        state.last_pong = Instant::now();
+    }
+
+    /// Starts waiting for pongs again, for when they
+    /// couldn't be read, without forgetting the latency.
+    pub(crate) fn restart_timeout(&self) {
+        self.state.lock().unwrap().last_pong = Instant::now();
+    }
+
+    pub(crate) fn latency(&self) -> Option<Duration> {
+        self.state.lock().unwrap().latency
+    }
+
+    /// How long it's been since the last pong, or since connecting if there wasn't any.
+    pub(crate) fn since_last_pong(&self) -> Duration {
+        self.state.lock().unwrap().last_pong.elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_latency_from_ping_data() {
+        let heartbeat = Heartbeat::new();
+        assert_eq!(heartbeat.latency(), None);
+
+        let data = heartbeat.ping_data();
+        std::thread::sleep(Duration::from_millis(20));
+        heartbeat.pong(data);
+
+        let latency = heartbeat.latency().unwrap();
+        assert!(latency >= Duration::from_millis(20), "{:?}", latency);
+        assert!(heartbeat.since_last_pong() < latency);
+
+        heartbeat.reset();
+        assert_eq!(heartbeat.latency(), None);
+    }
+}
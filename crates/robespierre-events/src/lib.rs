@@ -10,11 +10,22 @@ use robespierre_models::{
     events::{ClientToServerEvent, ServerToClientEvent},
     id::ChannelId,
 };
-use std::result::Result as StdResult;
+use std::{result::Result as StdResult, sync::Arc, time::Duration};
 use tokio::{net::TcpStream, sync::mpsc::UnboundedSender, time::Interval};
 use tokio_rustls::client::TlsStream;
 
+mod builder;
+pub mod dispatch;
+pub mod format;
+mod heartbeat;
+pub mod reconnect;
 pub mod typing;
+pub mod voso;
+
+pub use builder::ConnectionBuilder;
+pub use dispatch::DispatchStrategy;
+pub use format::WireFormat;
+pub use reconnect::ReconnectPolicy;
 
 /// Errors that can occur while working with ws messages / events.
 #[derive(Debug, thiserror::Error)]
@@ -25,24 +36,52 @@ pub enum EventsError {
     #[error("serialization / deserialization error: {0}")]
     DeserializationError(#[from] serde_json::Error),
 
+    #[error("msgpack decoding error: {0}")]
+    MsgpackDecodeError(#[from] rmp_serde::decode::Error),
+
+    #[error("msgpack encoding error: {0}")]
+    MsgpackEncodeError(#[from] rmp_serde::encode::Error),
+
     #[error("error while authenticating: {0}")]
     AuthError(String),
 
     #[error("websocket closed")]
     Closed,
+
+    #[error("timed out opening the websocket")]
+    HandshakeTimeout,
+
+    #[error("timed out waiting for the server to reply to the authentication")]
+    AuthTimeout,
+
+    #[error("the server stopped replying to pings")]
+    HeartbeatTimeout,
+
+    #[error("voso error: {0}")]
+    VosoError(String),
 }
 
 pub type Result<T = ()> = StdResult<T, EventsError>;
 
+type WsStream =
+    WebSocketStream<Stream<TokioAdapter<TcpStream>, TokioAdapter<TlsStream<TcpStream>>>>;
+
 struct ConnectionInternal {
-    stream: WebSocketStream<Stream<TokioAdapter<TcpStream>, TokioAdapter<TlsStream<TcpStream>>>>,
+    stream: WsStream,
     closed: bool,
+    strict: bool,
+    format: WireFormat,
+    heartbeat: Arc<heartbeat::Heartbeat>,
 }
 
 /// A websocket connection.
 pub struct Connection {
     internal: ConnectionInternal,
     ping_interval: Interval,
+    token: String,
+    heartbeat: Arc<heartbeat::Heartbeat>,
+    /// What it was built with, to reconnect the same way.
+    options: ConnectionBuilder,
 }
 
 /// A value that can be used to authenticate on the websocket, either as a bot or as a non-bot user.
@@ -52,6 +91,15 @@ pub enum Authentication<'a> {
     User { session_token: &'a str },
 }
 
+impl<'a> Authentication<'a> {
+    fn token(&self) -> &'a str {
+        match self {
+            Self::Bot { token } => token,
+            Self::User { session_token } => session_token,
+        }
+    }
+}
+
 impl<'a> From<&'a Session> for Authentication<'a> {
     fn from(s: &'a Session) -> Self {
         Self::User {
@@ -64,6 +112,18 @@ impl<'a> From<&'a Session> for Authentication<'a> {
 pub trait RawEventHandler: Send + Sync + Clone + 'static {
     type Context: 'static;
     async fn handle(self, ctx: Self::Context, event: ServerToClientEvent);
+
+    /// Called by [`Connection::run_with_reconnect`] after the connection was lost
+    /// and reestablished.
+    ///
+    /// Events that happened while disconnected were missed; the server
+    /// sends a new [`ServerToClientEvent::Ready`] event after this.
+    #[allow(unused_variables)]
+    async fn handle_reconnect(self, ctx: Self::Context)
+    where
+        Self::Context: Send,
+    {
+    }
 }
 
 /// A message to a [`Connection`]
@@ -78,15 +138,23 @@ pub enum ConnectionMessage {
 }
 
 #[derive(Clone, Debug)]
-pub struct ConnectionMessanger(UnboundedSender<ConnectionMessage>);
+pub struct ConnectionMessanger {
+    sender: UnboundedSender<ConnectionMessage>,
+    heartbeat: Arc<heartbeat::Heartbeat>,
+}
 
 impl ConnectionMessanger {
     /// Sends a message to the [`Connection`], describing something it should do.
     pub fn send(&self, message: ConnectionMessage) {
-        self.0
+        self.sender
             .send(message)
             .expect("Something went terribly wrong and the receiver closed");
     }
+
+    /// See [`Connection::latency`].
+    pub fn latency(&self) -> Option<Duration> {
+        self.heartbeat.latency()
+    }
 }
 
 /// Trait implemented on types that can be passed as a context to [`Connection::run`],
@@ -99,9 +167,14 @@ pub trait Context: Sized + Clone + Send + 'static {
 }
 
 impl Connection {
+    /// Creates a [`ConnectionBuilder`], to configure the connection before connecting.
+    pub fn builder() -> ConnectionBuilder {
+        ConnectionBuilder::new()
+    }
+
     /// Connects to the websocket, and authenticates, returning the socket or an error if it failed.
     pub async fn connect<'a>(auth: impl Into<Authentication<'a>>) -> Result<Self> {
-        Self::connect_with_url(auth, "wss://ws.revolt.chat").await
+        Self::builder().connect(auth).await
     }
 
     /// Connects to the websocket on the specified url, and authenticates, returning the socket or an error if it failed.
@@ -111,20 +184,87 @@ impl Connection {
         auth: impl Into<Authentication<'a>>,
         url: &str,
     ) -> Result<Self> {
-        tracing::debug!("Connecting to websocket on {}", url);
-        let (stream, _response) = connect_async(url).await?;
-        let mut internal = ConnectionInternal {
-            stream,
-            closed: false,
-        };
-        internal.authenticate(auth.into()).await?;
+        Self::builder().url(url).connect(auth).await
+    }
 
-        let connection = Self {
-            internal,
-            ping_interval: tokio::time::interval(std::time::Duration::from_secs(15)),
-        };
+    /// Connects to the websocket on the specified url, negotiating the given
+    /// [`WireFormat`], and authenticates, returning the socket or an error if it failed.
+    pub async fn connect_with_format<'a>(
+        auth: impl Into<Authentication<'a>>,
+        url: &str,
+        format: WireFormat,
+    ) -> Result<Self> {
+        Self::builder().url(url).format(format).connect(auth).await
+    }
+
+    /// The round-trip time of the last ping, or `None` if the
+    /// server didn't reply to any ping yet.
+    ///
+    /// Handlers can get it from [`ConnectionMessanger::latency`].
+    pub fn latency(&self) -> Option<Duration> {
+        self.heartbeat.latency()
+    }
+
+    /// Whether to fail on events that this version of the library doesn't know
+    /// about, instead of returning them as [`ServerToClientEvent::Unknown`], and on
+    /// unknown fields. Defaults to `false`.
+    ///
+    /// Useful to find out what changed on the server while developing.
+    /// See [`ServerToClientEvent::from_json`].
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.internal.strict = strict;
+        self.options.strict = strict;
+        self
+    }
+
+    /// Drops the current websocket, and connects and authenticates again,
+    /// on the same url and with the same token.
+    pub async fn reconnect(&mut self) -> Result {
+        self.internal =
+            ConnectionInternal::connect(&self.options, &self.token, Arc::clone(&self.heartbeat))
+                .await?;
+        self.ping_interval.reset();
+
+        Ok(())
+    }
+
+    /// Reconnects according to `policy`, after the connection failed with `error`,
+    /// and starts typing again in the channels in `typing_sessions`.
+    async fn reconnect_with_policy(
+        &mut self,
+        policy: &ReconnectPolicy,
+        mut error: EventsError,
+        typing_sessions: &typing::TypingSessionManager,
+    ) -> Result {
+        let mut attempt = 1;
+
+        while policy.can_retry(attempt) {
+            let delay = policy.delay(attempt);
+            tracing::warn!("Websocket failed ({}), reconnecting in {:?}", error, delay);
+            tokio::time::sleep(delay).await;
+
+            let result = async {
+                self.reconnect().await?;
+                for session in typing_sessions.current_sessions() {
+                    self.start_typing(*session).await?;
+                }
+                Ok(())
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    tracing::info!("Reconnected to the websocket");
+                    return Ok(());
+                }
+                Err(e) if reconnect::is_recoverable(&e) => error = e,
+                Err(e) => return Err(e),
+            }
 
-        Ok(connection)
+            attempt += 1;
+        }
+
+        Err(error)
     }
 
     /// Runs the "main loop", listening for events on the websocket and
@@ -135,13 +275,53 @@ impl Connection {
     /// use [`Connection::get_event`] to get events and [`Connection::hb`]
     /// to "heartbeat"(send a ping message to the server so it doesn't
     /// close the socket).
-    pub async fn run<C, H>(mut self, ctx: C, handler: H) -> Result
+    ///
+    /// Returns the first error, including [`EventsError::Closed`] if the
+    /// server closed the websocket; see [`Connection::run_with_reconnect`]
+    /// to reconnect instead.
+    pub async fn run<C, H>(self, ctx: C, handler: H) -> Result
+    where
+        C: Context,
+        H: RawEventHandler<Context = C>,
+    {
+        self.run_inner(ctx, handler, None).await
+    }
+
+    /// Like [`Connection::run`], but reconnects when the websocket is closed
+    /// or fails, according to `policy`.
+    ///
+    /// After reconnecting, the typing sessions that were active are started
+    /// again, and [`RawEventHandler::handle_reconnect`] is called. Events that
+    /// fail to deserialize are skipped instead of stopping the loop.
+    ///
+    /// Returns when the connection is closed with [`ConnectionMessage::Close`],
+    /// authentication fails, or `policy` gives up reconnecting.
+    pub async fn run_with_reconnect<C, H>(
+        self,
+        ctx: C,
+        handler: H,
+        policy: ReconnectPolicy,
+    ) -> Result
+    where
+        C: Context,
+        H: RawEventHandler<Context = C>,
+    {
+        self.run_inner(ctx, handler, Some(policy)).await
+    }
+
+    async fn run_inner<C, H>(
+        mut self,
+        ctx: C,
+        handler: H,
+        policy: Option<ReconnectPolicy>,
+    ) -> Result
     where
         C: Context,
         H: RawEventHandler<Context = C>,
     {
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ConnectionMessage>();
 
+        #[allow(clippy::large_enum_variant)]
         enum Event {
             FromServer(Result<ServerToClientEvent>),
             ConnectionMessage(Option<ConnectionMessage>),
@@ -150,6 +330,9 @@ impl Connection {
         }
 
         let mut typing_session_manager = typing::TypingSessionManager::default();
+        let mut dispatcher = dispatch::Dispatcher::new(self.options.dispatch);
+        // room for the next event, reserved before reading it
+        let mut permit = None;
 
         loop {
             // Event::FromServer = we got an event from the server, which we should pass to the handler
@@ -157,66 +340,110 @@ impl Connection {
             // Event::Tick = we didn't get any event, but we have to ping the server or it will close the connection
             // Event::TypingManagerTick = we didn't get any event, but we have to send all the "BeginTyping" events to the server or it will timeout and close them.
 
-            let Self {
-                internal,
-                ping_interval,
-            } = &mut self;
+            // Ok(true) = the connection was closed with ConnectionMessage::Close
+            let step = async {
+                let Self {
+                    internal,
+                    ping_interval,
+                    ..
+                } = &mut self;
 
-            let event = futures::select! {
-                event = internal.get_event().fuse() => Event::FromServer(event),
-                connection_message = rx.recv().fuse() => Event::ConnectionMessage(connection_message),
-                _ = ping_interval.tick().fuse() => Event::Tick,
-                _ = typing_session_manager.tick().fuse() => Event::TypingManagerTick,
-            };
+                // the next event is only read once there's room to handle it,
+                // but the heartbeat and typing sessions go on while waiting
+                let next_event = async {
+                    if permit.is_none() {
+                        permit = Some(dispatcher.reserve().await);
+                    }
+                    internal.get_event().await
+                };
 
-            match event {
-                Event::FromServer(event) => {
-                    let event = event?;
+                let event = futures::select! {
+                    event = next_event.fuse() => Event::FromServer(event),
+                    connection_message = rx.recv().fuse() => Event::ConnectionMessage(connection_message),
+                    _ = ping_interval.tick().fuse() => Event::Tick,
+                    _ = typing_session_manager.tick().fuse() => Event::TypingManagerTick,
+                };
 
-                    let handler = handler.clone();
-                    let ctx = ctx.clone().set_messanger(ConnectionMessanger(tx.clone()));
+                match event {
+                    Event::FromServer(event) => {
+                        let event = event?;
 
-                    let fut = handler.handle(ctx, event);
-                    tokio::spawn(fut);
-                }
-                Event::ConnectionMessage(Some(message)) => match message {
-                    ConnectionMessage::StartTyping { channel } => {
-                        typing_session_manager.start_typing(channel);
-                        self.start_typing(channel).await?;
+                        let handler = handler.clone();
+                        let ctx = ctx.clone().set_messanger(self.messanger(&tx));
+
+                        let key = dispatch::DispatchKey::of(&event);
+                        let fut = handler.handle(ctx, event);
+                        dispatcher.dispatch(key, fut, permit.take().unwrap_or_default());
                     }
-                    ConnectionMessage::StopTyping { channel } => {
-                        if typing_session_manager.stop_typing(channel) {
-                            // was removed
-                            self.stop_typing(channel).await?;
+                    Event::ConnectionMessage(Some(message)) => match message {
+                        ConnectionMessage::StartTyping { channel } => {
+                            typing_session_manager.start_typing(channel);
+                            self.start_typing(channel).await?;
+                        }
+                        ConnectionMessage::StopTyping { channel } => {
+                            if typing_session_manager.stop_typing(channel) {
+                                // was removed
+                                self.stop_typing(channel).await?;
+                            }
                         }
+                        ConnectionMessage::Close => {
+                            self.internal.close().await?;
+                            return Ok(true);
+                        }
+                    },
+                    Event::ConnectionMessage(None) => {
+                        // can never happen as the tx is never moved outside of this function,
+                        // only cloned, and therefore at least one sender is not dropped
+                        // also, the receiver is never dropped / closed
+
+                        // (unless ? propagates the error in which case this block shouldn't be reached)
+                        unreachable!()
                     }
-                    ConnectionMessage::Close => {
-                        self.close().await?;
-                        return Ok(()); // will drop self
+                    Event::Tick => {
+                        if permit.is_none() && dispatcher.is_full() {
+                            // the pongs aren't read either while waiting for room,
+                            // so the server can't be blamed for not replying
+                            self.heartbeat.restart_timeout();
+                        }
+                        self.hb().await?;
+                    }
+                    Event::TypingManagerTick => {
+                        for session in typing_session_manager.current_sessions() {
+                            self.start_typing(*session).await?;
+                        }
                     }
-                },
-                Event::ConnectionMessage(None) => {
-                    // can never happen as the tx is never moved outside of this function,
-                    // only cloned, and therefore at least one sender is not dropped
-                    // also, the receiver is never dropped / closed
-
-                    // (unless ? propagates the error in which case this block shouldn't be reached)
-                    unreachable!()
                 }
-                Event::Tick => {
-                    self.hb().await?;
+
+                Ok(false)
+            };
+
+            match step.await {
+                Ok(true) => return Ok(()),
+                Ok(false) => {}
+                Err(
+                    e @ (EventsError::DeserializationError(_) | EventsError::MsgpackDecodeError(_)),
+                ) if policy.is_some() => {
+                    tracing::warn!("Skipping event that failed to deserialize: {}", e);
                 }
-                Event::TypingManagerTick => {
-                    for session in typing_session_manager.current_sessions() {
-                        self.start_typing(*session).await?;
+                Err(e) => match &policy {
+                    Some(policy) if reconnect::is_recoverable(&e) => {
+                        self.reconnect_with_policy(policy, e, &typing_session_manager)
+                            .await?;
+
+                        // not an event read from the websocket, so it doesn't need room
+                        let ctx = ctx.clone().set_messanger(self.messanger(&tx));
+                        let fut = handler.clone().handle_reconnect(ctx);
+                        dispatcher.dispatch(None, fut, dispatch::Permit::default());
                     }
-                }
+                    _ => return Err(e),
+                },
             }
         }
     }
 
     /// Suitable for lower-level, manual handling of events.
     pub async fn next(&mut self) -> Result<ServerToClientEvent> {
+        #[allow(clippy::large_enum_variant)]
         enum Event {
             FromServer(Result<ServerToClientEvent>),
             Tick,
@@ -226,6 +453,7 @@ impl Connection {
             let Self {
                 internal,
                 ping_interval,
+                ..
             } = self;
 
             let event = futures::select! {
@@ -245,8 +473,25 @@ impl Connection {
         }
     }
 
+    fn messanger(&self, sender: &UnboundedSender<ConnectionMessage>) -> ConnectionMessanger {
+        ConnectionMessanger {
+            sender: sender.clone(),
+            heartbeat: Arc::clone(&self.heartbeat),
+        }
+    }
+
     /// Sends a ping message to the server, so it doesn't close the connection.
+    ///
+    /// Fails with [`EventsError::HeartbeatTimeout`] if the server didn't reply
+    /// to the previous pings for longer than [`ConnectionBuilder::heartbeat_timeout`].
     pub async fn hb(&mut self) -> Result {
+        if let Some(timeout) = self.options.heartbeat_timeout {
+            if self.heartbeat.since_last_pong() > timeout {
+                tracing::warn!("No pong for {:?}", self.heartbeat.since_last_pong());
+                return Err(EventsError::HeartbeatTimeout);
+            }
+        }
+
         self.internal.hb().await
     }
 
@@ -280,23 +525,72 @@ impl Connection {
     }
 }
 
+/// Runs `future`, failing with `error` if it doesn't finish in `timeout`.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    error: EventsError,
+    future: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| error)?,
+        None => future.await,
+    }
+}
+
 impl ConnectionInternal {
+    fn received(&self, event: ServerToClientEvent) -> ServerToClientEvent {
+        if let ServerToClientEvent::Pong { data } = &event {
+            self.heartbeat.pong(*data);
+        }
+
+        event
+    }
+
+    async fn connect(
+        options: &ConnectionBuilder,
+        token: &str,
+        heartbeat: Arc<heartbeat::Heartbeat>,
+    ) -> Result<Self> {
+        let url = options.format.url(&options.url);
+        tracing::debug!("Connecting to websocket on {}", url);
+        let (stream, _response) = with_timeout(
+            options.handshake_timeout,
+            EventsError::HandshakeTimeout,
+            async { Ok(connect_async(url).await?) },
+        )
+        .await?;
+
+        heartbeat.reset();
+        let mut internal = Self {
+            stream,
+            closed: false,
+            strict: options.strict,
+            format: options.format,
+            heartbeat,
+        };
+        with_timeout(
+            options.auth_timeout,
+            EventsError::AuthTimeout,
+            internal.authenticate(token),
+        )
+        .await?;
+
+        Ok(internal)
+    }
+
     async fn hb(&mut self) -> Result {
-        self.send_event(ClientToServerEvent::Ping { data: 0 })
-            .await?;
+        let data = self.heartbeat.ping_data();
+        self.send_event(ClientToServerEvent::Ping { data }).await?;
 
         Ok(())
     }
 
-    async fn authenticate(&mut self, auth: Authentication<'_>) -> Result {
+    async fn authenticate(&mut self, token: &str) -> Result {
         tracing::debug!("Authenticating");
-        self.send_event(match &auth {
-            Authentication::Bot { token } => ClientToServerEvent::Authenticate {
-                token: token.to_string(),
-            },
-            Authentication::User { session_token } => ClientToServerEvent::Authenticate {
-                token: session_token.to_string(),
-            },
+        self.send_event(ClientToServerEvent::Authenticate {
+            token: token.to_string(),
         })
         .await?;
 
@@ -320,11 +614,19 @@ impl ConnectionInternal {
     async fn send_event(&mut self, message: ClientToServerEvent) -> Result {
         use futures::sink::SinkExt;
 
-        let json = serde_json::to_string(&message)?;
-
-        tracing::debug!("[>] {}", &json);
-
-        self.stream.send(TungsteniteMessage::text(json)).await?;
+        let message = match self.format {
+            WireFormat::Json => {
+                let json = serde_json::to_string(&message)?;
+                tracing::debug!("[>] {}", &json);
+                TungsteniteMessage::text(json)
+            }
+            WireFormat::MessagePack => {
+                tracing::debug!("[>] {:?}", &message);
+                // named, so that structs are maps, like they are in json
+                TungsteniteMessage::binary(rmp_serde::to_vec_named(&message)?)
+            }
+        };
+        self.stream.send(message).await?;
 
         Ok(())
     }
@@ -342,28 +644,423 @@ impl ConnectionInternal {
         }
 
         use async_std::stream::StreamExt;
-        let msg: TungsteniteMessage = self
-            .stream
-            .next()
+        loop {
+            let msg: TungsteniteMessage = match self.stream.next().await {
+                Some(msg) => msg?,
+                None => {
+                    tracing::debug!("Websocket stream ended without a close frame");
+                    self.closed = true;
+
+                    return Err(EventsError::Closed);
+                }
+            };
+
+            match msg {
+                TungsteniteMessage::Text(json) => {
+                    tracing::debug!("[<] {}", &json);
+                    return Ok(self.received(ServerToClientEvent::from_json(&json, self.strict)?));
+                }
+                TungsteniteMessage::Binary(data) => {
+                    return Ok(self.received(format::decode_event(&data, self.strict)?));
+                }
+                TungsteniteMessage::Ping(ping) => tracing::debug!("Got ping: {:?}", &ping),
+                TungsteniteMessage::Pong(pong) => tracing::debug!("Got pong: {:?}", &pong),
+                TungsteniteMessage::Close(close) => {
+                    tracing::debug!("Got close: {:?}", close);
+                    self.closed = true;
+
+                    return Err(EventsError::Closed);
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_tungstenite::{
+        tokio::{accept_async, accept_hdr_async},
+        tungstenite::handshake::server::{Request, Response},
+    };
+    use futures::{SinkExt, StreamExt};
+    use tokio::{net::TcpListener, sync::mpsc};
+
+    use super::*;
+
+    const CHANNEL: &str = "01FE9XVK2GXQXHZ5BCZBD6PA8P";
+    const USER: &str = "01FE9XNFEDH6NSPKRQY5SVXYRP";
+
+    #[derive(Clone)]
+    struct TestContext(
+        Option<ConnectionMessanger>,
+        mpsc::UnboundedSender<&'static str>,
+    );
+
+    impl Context for TestContext {
+        fn set_messanger(self, messanger: ConnectionMessanger) -> Self {
+            Self(Some(messanger), self.1)
+        }
+    }
+
+    #[derive(Clone)]
+    struct Handler;
+
+    #[async_trait::async_trait]
+    impl RawEventHandler for Handler {
+        type Context = TestContext;
+
+        async fn handle(self, ctx: Self::Context, event: ServerToClientEvent) {
+            if let ServerToClientEvent::ChannelStartTyping { id, .. } = event {
+                ctx.0
+                    .unwrap()
+                    .send(ConnectionMessage::StartTyping { channel: id });
+            }
+        }
+
+        async fn handle_reconnect(self, ctx: Self::Context) {
+            ctx.1.send("reconnected").unwrap();
+            ctx.0.unwrap().send(ConnectionMessage::Close);
+        }
+    }
+
+    /// Receives the next event, skipping pings.
+    async fn recv_event<S>(ws: &mut S) -> Option<serde_json::Value>
+    where
+        S: futures::Stream<Item = async_tungstenite::tungstenite::Result<TungsteniteMessage>>
+            + Unpin,
+    {
+        loop {
+            match ws.next().await?.unwrap() {
+                TungsteniteMessage::Text(json) => {
+                    let event: serde_json::Value = serde_json::from_str(&json).unwrap();
+                    if event["type"] != "Ping" {
+                        return Some(event);
+                    }
+                }
+                TungsteniteMessage::Close(_) => return None,
+                msg => panic!("unexpected message {:?}", msg),
+            }
+        }
+    }
+
+    /// Accepts a connection, and replies to the authentication.
+    async fn accept_authenticated(
+        listener: &TcpListener,
+    ) -> WebSocketStream<TokioAdapter<TcpStream>> {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+
+        let auth = recv_event(&mut ws).await.unwrap();
+        assert_eq!(auth["type"], "Authenticate");
+        assert_eq!(auth["token"], "token");
+        ws.send(TungsteniteMessage::text(r#"{"type":"Authenticated"}"#))
             .await
-            .expect("Last message in ws without closing")?;
+            .unwrap();
 
-        match msg {
-            TungsteniteMessage::Text(json) => {
-                tracing::debug!("[<] {}", &json);
-                return Ok(serde_json::from_str(&json)?);
+        ws
+    }
+
+    /// Makes the client start typing, drops the connection and then
+    /// expects the typing session to be restored on the new one.
+    async fn serve(listener: TcpListener) {
+        let mut ws = accept_authenticated(&listener).await;
+        let event = format!(
+            r#"{{"type":"ChannelStartTyping","id":"{}","user":"{}"}}"#,
+            CHANNEL, USER
+        );
+        ws.send(TungsteniteMessage::text(event)).await.unwrap();
+        let typing = recv_event(&mut ws).await.unwrap();
+        assert_eq!(typing["type"], "BeginTyping");
+        drop(ws);
+
+        let mut ws = accept_authenticated(&listener).await;
+        let typing = recv_event(&mut ws).await.unwrap();
+        assert_eq!(typing["type"], "BeginTyping");
+        assert_eq!(typing["channel"], CHANNEL);
+
+        // closed by the handler
+        while recv_event(&mut ws).await.is_some() {}
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_restores_typing() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(listener));
+
+        let connection = Connection::connect_with_url(Authentication::Bot { token: "token" }, &url)
+            .await
+            .unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let policy = ReconnectPolicy::default().base_delay(std::time::Duration::from_millis(10));
+        connection
+            .run_with_reconnect(TestContext(None, tx), Handler, policy)
+            .await
+            .unwrap();
+
+        assert_eq!(rx.recv().await, Some("reconnected"));
+        server.await.unwrap();
+    }
+
+    /// Accepts a connection speaking msgpack, checking that it
+    /// was negotiated, and replies to the authentication.
+    async fn accept_msgpack(listener: &TcpListener) -> WebSocketStream<TokioAdapter<TcpStream>> {
+        let (stream, _) = listener.accept().await.unwrap();
+        // the signature of the callback is imposed by tungstenite
+        #[allow(clippy::result_large_err)]
+        let check_format = |request: &Request, response: Response| {
+            assert_eq!(request.uri().query(), Some("format=msgpack"));
+            Ok(response)
+        };
+        let mut ws = accept_hdr_async(stream, check_format).await.unwrap();
+
+        let auth = recv_msgpack(&mut ws).await.unwrap();
+        assert_eq!(auth["type"], "Authenticate");
+        send_msgpack(&mut ws, serde_json::json!({"type": "Authenticated"})).await;
+
+        ws
+    }
+
+    /// Receives the next msgpack event, skipping pings.
+    async fn recv_msgpack(
+        ws: &mut WebSocketStream<TokioAdapter<TcpStream>>,
+    ) -> Option<serde_json::Value> {
+        loop {
+            match ws.next().await?.unwrap() {
+                TungsteniteMessage::Binary(data) => {
+                    let event = format::decode_binary(&data).unwrap();
+                    if event["type"] != "Ping" {
+                        return Some(event);
+                    }
+                }
+                TungsteniteMessage::Close(_) => return None,
+                msg => panic!("unexpected message {:?}", msg),
             }
-            TungsteniteMessage::Binary(b) => tracing::debug!("Got binary: {:?}", &b),
-            TungsteniteMessage::Ping(ping) => tracing::debug!("Got ping: {:?}", &ping),
-            TungsteniteMessage::Pong(pong) => tracing::debug!("Got pong: {:?}", &pong),
-            TungsteniteMessage::Close(close) => {
-                tracing::debug!("Got close: {:?}", close);
-                self.closed = true;
-
-                return Err(EventsError::Closed);
+        }
+    }
+
+    async fn send_msgpack(
+        ws: &mut WebSocketStream<TokioAdapter<TcpStream>>,
+        event: serde_json::Value,
+    ) {
+        let data = rmp_serde::to_vec_named(&event).unwrap();
+        ws.send(TungsteniteMessage::binary(data)).await.unwrap();
+    }
+
+    async fn serve_msgpack(listener: TcpListener) {
+        let mut ws = accept_msgpack(&listener).await;
+
+        let replies = [
+            serde_json::json!({"type": "EmojiCreate", "id": CHANNEL}),
+            serde_json::json!({"type": "ChannelStartTyping", "id": CHANNEL, "user": USER}),
+        ];
+        for reply in replies {
+            send_msgpack(&mut ws, reply).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn decodes_msgpack_events() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_msgpack(listener));
+
+        let mut connection = Connection::connect_with_format(
+            Authentication::Bot { token: "token" },
+            &url,
+            WireFormat::MessagePack,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(
+            connection.next().await.unwrap(),
+            ServerToClientEvent::Unknown { kind, .. } if kind == "EmojiCreate"
+        ));
+        assert_eq!(
+            connection.next().await.unwrap(),
+            ServerToClientEvent::ChannelStartTyping {
+                id: CHANNEL.parse().unwrap(),
+                user: USER.parse().unwrap(),
             }
+        );
+        server.await.unwrap();
+    }
+
+    /// Sends a frame that isn't msgpack, then expects the
+    /// connection to keep handling events.
+    async fn serve_bad_msgpack(listener: TcpListener) {
+        let mut ws = accept_msgpack(&listener).await;
+        ws.send(TungsteniteMessage::binary(vec![0xc1]))
+            .await
+            .unwrap();
+        let event = serde_json::json!({"type": "ChannelStartTyping", "id": CHANNEL, "user": USER});
+        send_msgpack(&mut ws, event).await;
+
+        let typing = recv_msgpack(&mut ws).await.unwrap();
+        assert_eq!(typing["type"], "BeginTyping");
+        drop(ws);
+
+        let mut ws = accept_msgpack(&listener).await;
+        // closed by the handler
+        while recv_msgpack(&mut ws).await.is_some() {}
+    }
+
+    #[tokio::test]
+    async fn skips_bad_msgpack_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_bad_msgpack(listener));
+
+        let connection = Connection::connect_with_format(
+            Authentication::Bot { token: "token" },
+            &url,
+            WireFormat::MessagePack,
+        )
+        .await
+        .unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let policy = ReconnectPolicy::default().base_delay(std::time::Duration::from_millis(10));
+        connection
+            .run_with_reconnect(TestContext(None, tx), Handler, policy)
+            .await
+            .unwrap();
+
+        assert_eq!(rx.recv().await, Some("reconnected"));
+        server.await.unwrap();
+    }
+
+    /// Replies to the first ping only.
+    async fn serve_one_pong(listener: TcpListener) {
+        let mut ws = accept_authenticated(&listener).await;
+
+        let ping = match ws.next().await.unwrap().unwrap() {
+            TungsteniteMessage::Text(json) => {
+                serde_json::from_str::<serde_json::Value>(&json).unwrap()
+            }
+            msg => panic!("unexpected message {:?}", msg),
         };
+        assert_eq!(ping["type"], "Ping");
+        let pong = serde_json::json!({"type": "Pong", "data": ping["data"]});
+        ws.send(TungsteniteMessage::text(pong.to_string()))
+            .await
+            .unwrap();
+
+        while recv_event(&mut ws).await.is_some() {}
+    }
+
+    #[tokio::test]
+    async fn measures_latency_and_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve_one_pong(listener));
+
+        let mut connection = Connection::builder()
+            .url(url)
+            .heartbeat_interval(Duration::from_millis(20))
+            .heartbeat_timeout(Duration::from_millis(100))
+            .connect(Authentication::Bot { token: "token" })
+            .await
+            .unwrap();
+        assert_eq!(connection.latency(), None);
+
+        assert!(matches!(
+            connection.next().await,
+            Err(EventsError::HeartbeatTimeout)
+        ));
+        assert!(connection.latency().is_some());
+
+        connection.close().await.unwrap();
+        server.await.unwrap();
+    }
+
+    /// Takes a while to handle typing events, telling when it does.
+    #[derive(Clone)]
+    struct SlowHandler(Arc<AtomicBool>);
+
+    #[async_trait::async_trait]
+    impl RawEventHandler for SlowHandler {
+        type Context = TestContext;
+
+        async fn handle(self, ctx: Self::Context, event: ServerToClientEvent) {
+            match event {
+                ServerToClientEvent::ChannelStartTyping { .. } => {
+                    self.0.store(true, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    self.0.store(false, Ordering::SeqCst);
+                }
+                ServerToClientEvent::ChannelStopTyping { .. } => {
+                    ctx.0.unwrap().send(ConnectionMessage::Close);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Sends an event that takes a while to handle, and another one right
+    /// after it, and counts the pings received while `handling`, replying to all of them.
+    async fn serve_slow_events(listener: TcpListener, handling: Arc<AtomicBool>) -> usize {
+        let mut ws = accept_authenticated(&listener).await;
+        for kind in ["ChannelStartTyping", "ChannelStopTyping"] {
+            let event = serde_json::json!({"type": kind, "id": CHANNEL, "user": USER});
+            ws.send(TungsteniteMessage::text(event.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let mut pings = 0;
+        while let Some(msg) = ws.next().await {
+            match msg.unwrap() {
+                TungsteniteMessage::Text(json) => {
+                    let ping: serde_json::Value = serde_json::from_str(&json).unwrap();
+                    assert_eq!(ping["type"], "Ping");
+                    if handling.load(Ordering::SeqCst) {
+                        pings += 1;
+                    }
+
+                    let pong = serde_json::json!({"type": "Pong", "data": ping["data"]});
+                    ws.send(TungsteniteMessage::text(pong.to_string()))
+                        .await
+                        .unwrap();
+                }
+                TungsteniteMessage::Close(_) => break,
+                msg => panic!("unexpected message {:?}", msg),
+            }
+        }
+
+        pings
+    }
+
+    #[tokio::test]
+    async fn heartbeat_goes_on_while_waiting_for_handlers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let handling = Arc::new(AtomicBool::new(false));
+        let server = tokio::spawn(serve_slow_events(listener, Arc::clone(&handling)));
+
+        let connection = Connection::builder()
+            .url(url)
+            .dispatch(DispatchStrategy::Sequential { max_in_flight: 1 })
+            .heartbeat_interval(Duration::from_millis(20))
+            .heartbeat_timeout(Duration::from_millis(60))
+            .connect(Authentication::Bot { token: "token" })
+            .await
+            .unwrap();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let result = connection
+            .run(TestContext(None, tx), SlowHandler(handling))
+            .await;
+        assert!(
+            !matches!(result, Err(EventsError::HeartbeatTimeout)),
+            "timed out while the handler held the only permit"
+        );
+        result.unwrap();
 
-        unimplemented!()
+        // the second event can't be dispatched until the first one is handled,
+        // which takes far longer than the heartbeat interval
+        let pings = server.await.unwrap();
+        assert!(pings >= 1, "no pings while handling the event");
     }
 }
@@ -0,0 +1,96 @@
+//! Reconnecting to the websocket after the connection was lost.
+
+use std::time::Duration;
+
+use crate::EventsError;
+
+/// Describes how [`crate::Connection::run_with_reconnect`] reconnects after
+/// the websocket was closed or failed.
+///
+/// The delay between two attempts doubles after every failed attempt, and
+/// goes back to the base delay once the connection is reestablished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    max_attempts: Option<u32>,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    /// Keeps trying to reconnect forever, starting with a delay of 1 second
+    /// and waiting at most 1 minute between attempts.
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The maximum number of times to try reconnecting in a row, before
+    /// giving up and returning the error; `None` never gives up.
+    pub fn max_attempts(self, max_attempts: Option<u32>) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    /// The delay before the first attempt. Doubles with every following attempt.
+    pub fn base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+
+    /// The maximum delay between two attempts.
+    pub fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    pub(crate) fn can_retry(&self, attempt: u32) -> bool {
+        self.max_attempts.map_or(true, |max| attempt <= max)
+    }
+
+    /// The delay before the `attempt`-th attempt.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(1 << exp).min(self.max_delay)
+    }
+}
+
+/// Whether the connection can be recovered from `error` by reconnecting.
+///
+/// Authentication errors are not recoverable, as reconnecting would fail the same way.
+pub(crate) fn is_recoverable(error: &EventsError) -> bool {
+    matches!(
+        error,
+        EventsError::WsError(_)
+            | EventsError::Closed
+            | EventsError::HandshakeTimeout
+            | EventsError::AuthTimeout
+            | EventsError::HeartbeatTimeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = ReconnectPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500))
+            .max_attempts(Some(3));
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(4), Duration::from_millis(500));
+
+        assert!(policy.can_retry(3));
+        assert!(!policy.can_retry(4));
+        assert!(ReconnectPolicy::default().can_retry(u32::MAX));
+    }
+}
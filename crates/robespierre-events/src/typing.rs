@@ -70,7 +70,7 @@ impl TypingSessionManager {
         false
     }
 
-    pub fn current_sessions(&self) -> ChannelIdIter {
+    pub fn current_sessions(&self) -> ChannelIdIter<'_> {
         ChannelIdIter(self.sessions.keys())
     }
 
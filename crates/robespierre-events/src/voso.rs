@@ -0,0 +1,350 @@
+//! A client for the signalling websocket of voso, the voice server.
+//!
+//! Only the signalling part is implemented: joining a room and tracking
+//! who is in it; no media is sent or received.
+//!
+//! ```no_run
+//! # use robespierre_events::voso::VosoConnection;
+//! # use robespierre_models::{core::RevoltConfiguration, id::ChannelId};
+//! # async fn f(config: RevoltConfiguration, channel: ChannelId, token: String) -> robespierre_events::Result {
+//! // token is from `Http::join_call(channel)`
+//! let mut voso = VosoConnection::connect(config.features.voso.ws_url()).await?;
+//! voso.authenticate(channel, &token).await?;
+//! let mut room = voso.room_info().await?;
+//!
+//! loop {
+//!     let event = voso.next_event().await?;
+//!     room.apply(&event);
+//!     println!("in voice: {:?}", room.users.keys());
+//! }
+//! # }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+
+use async_tungstenite::{tokio::connect_async, tungstenite::Message as TungsteniteMessage};
+use futures::{SinkExt, StreamExt};
+use robespierre_models::id::{ChannelId, UserId};
+use serde::{Deserialize, Serialize};
+
+use crate::{EventsError, Result, WsStream};
+
+/// The data of the reply to [`VosoConnection::authenticate`].
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VosoAuthenticated {
+    pub user_id: UserId,
+    pub room_id: ChannelId,
+    #[serde(default)]
+    pub rtp_capabilities: serde_json::Value,
+}
+
+/// A user in a voice room.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct VosoUser {
+    #[serde(default)]
+    pub audio: bool,
+}
+
+/// The state of a voice room, from [`VosoConnection::room_info`].
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomInfo {
+    pub id: ChannelId,
+    #[serde(default)]
+    pub video_allowed: bool,
+    pub users: HashMap<UserId, VosoUser>,
+}
+
+impl RoomInfo {
+    /// Updates the room with an event received after fetching it.
+    pub fn apply(&mut self, event: &VosoEvent) {
+        match event {
+            VosoEvent::UserJoined { id } => {
+                self.users.entry(*id).or_default();
+            }
+            VosoEvent::UserLeft { id } => {
+                self.users.remove(id);
+            }
+            VosoEvent::UserStartProduce { id, kind } if kind == "audio" => {
+                self.users.entry(*id).or_default().audio = true;
+            }
+            VosoEvent::UserStopProduce { id, kind } if kind == "audio" => {
+                if let Some(user) = self.users.get_mut(id) {
+                    user.audio = false;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An event sent by voso about the room.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "data")]
+pub enum VosoEvent {
+    UserJoined {
+        id: UserId,
+    },
+    UserLeft {
+        id: UserId,
+    },
+    UserStartProduce {
+        id: UserId,
+        #[serde(rename = "type")]
+        kind: String,
+    },
+    UserStopProduce {
+        id: UserId,
+        #[serde(rename = "type")]
+        kind: String,
+    },
+    /// An event not known to the library.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize)]
+struct Request<T> {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Incoming {
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    data: serde_json::Value,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A connection to the signalling websocket of voso.
+pub struct VosoConnection {
+    stream: WsStream,
+    next_id: u64,
+    // events that came while waiting for a reply
+    events: VecDeque<VosoEvent>,
+}
+
+impl VosoConnection {
+    /// Connects to the websocket of voso, at `url`.
+    ///
+    /// The url is [`robespierre_models::core::Voso::ws_url`].
+    pub async fn connect(url: &str) -> Result<Self> {
+        tracing::debug!("Connecting to voso on {}", url);
+        let (stream, _response) = connect_async(url).await?;
+
+        Ok(Self {
+            stream,
+            next_id: 0,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Authenticates, joining the room of `channel` with a token
+    /// from `Http::join_call`.
+    pub async fn authenticate(
+        &mut self,
+        channel: ChannelId,
+        token: &str,
+    ) -> Result<VosoAuthenticated> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Authenticate<'a> {
+            room_id: ChannelId,
+            token: &'a str,
+        }
+
+        let data = self
+            .request(
+                "Authenticate",
+                Authenticate {
+                    room_id: channel,
+                    token,
+                },
+            )
+            .await?;
+
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Fetches the current state of the room.
+    pub async fn room_info(&mut self) -> Result<RoomInfo> {
+        let data = self.request("RoomInfo", ()).await?;
+
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Gets the next event about the room.
+    pub async fn next_event(&mut self) -> Result<VosoEvent> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(event);
+        }
+
+        loop {
+            let message = self.receive().await?;
+            if message.id.is_none() {
+                return Ok(serde_json::from_value(message.data)?);
+            }
+        }
+    }
+
+    /// Leaves the room, closing the websocket.
+    pub async fn close(mut self) -> Result {
+        self.stream.close(None).await?;
+
+        Ok(())
+    }
+
+    async fn request<T: Serialize>(
+        &mut self,
+        kind: &'static str,
+        data: T,
+    ) -> Result<serde_json::Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let json = serde_json::to_string(&Request { id, kind, data })?;
+        tracing::debug!("[voso >] {}", &json);
+        self.stream.send(TungsteniteMessage::text(json)).await?;
+
+        loop {
+            let message = self.receive().await?;
+            match message.id {
+                Some(reply_id) if reply_id == id => {
+                    return match message.error {
+                        Some(error) => Err(EventsError::VosoError(error)),
+                        None => Ok(message.data),
+                    };
+                }
+                Some(_) => {}
+                None => {
+                    // the event is ignored if it's malformed, as it
+                    // would be when received through next_event
+                    if let Ok(event) = serde_json::from_value(message.data) {
+                        self.events.push_back(event);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn receive(&mut self) -> Result<Incoming> {
+        loop {
+            let msg = match self.stream.next().await {
+                Some(msg) => msg?,
+                None => return Err(EventsError::Closed),
+            };
+
+            match msg {
+                TungsteniteMessage::Text(json) => {
+                    tracing::debug!("[voso <] {}", &json);
+                    let message = serde_json::from_str::<serde_json::Value>(&json)?;
+                    // events carry their type next to the data
+                    let mut incoming: Incoming = serde_json::from_value(message.clone())?;
+                    if incoming.id.is_none() {
+                        incoming.data = message;
+                    }
+                    return Ok(incoming);
+                }
+                TungsteniteMessage::Close(_) => return Err(EventsError::Closed),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_tungstenite::tokio::accept_async;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const CHANNEL: &str = "01FE9XVK2GXQXHZ5BCZBD6PA8P";
+    const ME: &str = "01FE9XNFEDH6NSPKRQY5SVXYRP";
+    const OTHER: &str = "01FE9XXP4NWJ6S2D4N7WX6W3AJ";
+
+    async fn recv_json<S>(ws: &mut S) -> serde_json::Value
+    where
+        S: futures::Stream<Item = async_tungstenite::tungstenite::Result<TungsteniteMessage>>
+            + Unpin,
+    {
+        match ws.next().await.unwrap().unwrap() {
+            TungsteniteMessage::Text(json) => serde_json::from_str(&json).unwrap(),
+            msg => panic!("unexpected message {:?}", msg),
+        }
+    }
+
+    /// Stands in for voso: replies to authenticate and room info, sending
+    /// an event in between, and then announces a user leaving.
+    async fn serve(listener: TcpListener) {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+
+        let auth = recv_json(&mut ws).await;
+        assert_eq!(auth["type"], "Authenticate");
+        assert_eq!(auth["data"]["roomId"], CHANNEL);
+        assert_eq!(auth["data"]["token"], "token");
+
+        let replies = [
+            format!(r#"{{"type":"UserJoined","data":{{"id":"{}"}}}}"#, OTHER),
+            format!(
+                r#"{{"id":{},"type":"Authenticate","data":{{"userId":"{}","roomId":"{}","rtpCapabilities":{{}}}}}}"#,
+                auth["id"], ME, CHANNEL
+            ),
+        ];
+        for reply in replies {
+            ws.send(TungsteniteMessage::text(reply)).await.unwrap();
+        }
+
+        let info = recv_json(&mut ws).await;
+        assert_eq!(info["type"], "RoomInfo");
+        let replies = [
+            format!(
+                r#"{{"id":{},"type":"RoomInfo","data":{{"id":"{}","videoAllowed":false,"users":{{"{}":{{"audio":false}}}}}}}}"#,
+                info["id"], CHANNEL, ME
+            ),
+            format!(r#"{{"type":"UserLeft","data":{{"id":"{}"}}}}"#, OTHER),
+        ];
+        for reply in replies {
+            ws.send(TungsteniteMessage::text(reply)).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn tracks_users_in_room() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(listener));
+
+        let channel: ChannelId = CHANNEL.parse().unwrap();
+        let me: UserId = ME.parse().unwrap();
+        let other: UserId = OTHER.parse().unwrap();
+
+        let mut voso = VosoConnection::connect(&url).await.unwrap();
+        let authenticated = voso.authenticate(channel, "token").await.unwrap();
+        assert_eq!(authenticated.user_id, me);
+        assert_eq!(authenticated.room_id, channel);
+
+        let mut room = voso.room_info().await.unwrap();
+        assert_eq!(room.users.len(), 1);
+
+        // received while authenticating
+        let event = voso.next_event().await.unwrap();
+        assert_eq!(event, VosoEvent::UserJoined { id: other });
+        room.apply(&event);
+        assert!(room.users.contains_key(&other));
+
+        let event = voso.next_event().await.unwrap();
+        assert_eq!(event, VosoEvent::UserLeft { id: other });
+        room.apply(&event);
+        assert_eq!(room.users.keys().collect::<Vec<_>>(), vec![&me]);
+
+        server.await.unwrap();
+    }
+}
@@ -4,20 +4,9 @@ use robespierre_models::{
 };
 
 use super::impl_prelude::*;
+use crate::request::Client;
 
 impl Http {
-    // account
-    pub async fn fetch_account(&self) -> Result<Account> {
-        Ok(self
-            .client_user_session_auth_type()
-            .get(ep!(self, "/auth/account"))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
-    }
-
     pub async fn create_account(
         email: &str,
         password: &str,
@@ -34,7 +23,7 @@ impl Http {
             captcha: Option<&'a str>,
         }
 
-        reqwest::Client::new()
+        Client::unauthenticated()
             .post(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/account/create"
@@ -46,8 +35,7 @@ impl Http {
                 captcha,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -60,28 +48,26 @@ impl Http {
             captcha: Option<&'a str>,
         }
 
-        reqwest::Client::new()
+        Client::unauthenticated()
             .post(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/account/reverify"
             ))
             .json(&ResendVerificationRequest { email, captcha })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
 
     pub async fn verify_email(code: &str) -> Result {
-        reqwest::Client::new()
+        Client::unauthenticated()
             .post(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/account/verify/{}" code
             ))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -94,15 +80,14 @@ impl Http {
             captcha: Option<&'a str>,
         }
 
-        reqwest::Client::new()
+        Client::unauthenticated()
             .post(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/account/reset_password"
             ))
             .json(&SendPasswordResetRequest { email, captcha })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -114,55 +99,14 @@ impl Http {
             token: &'a str,
         }
 
-        reqwest::Client::new()
+        Client::unauthenticated()
             .patch(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/account/reset_password"
             ))
             .json(&PasswordResetRequest { password, token })
             .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
-    }
-
-    pub async fn change_password(&self, password: &str, current_password: &str) -> Result {
-        #[derive(serde::Serialize)]
-        struct ChangePasswordRequest<'a> {
-            password: &'a str,
-            current_password: &'a str,
-        }
-
-        self.client_user_session_auth_type()
-            .post(ep!(self, "/auth/account/change/password"))
-            .json(&ChangePasswordRequest {
-                password,
-                current_password,
-            })
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
-    }
-
-    pub async fn change_email(&self, email: &str, current_password: &str) -> Result {
-        #[derive(serde::Serialize)]
-        struct ChangeEmailRequest<'a> {
-            email: &'a str,
-            current_password: &'a str,
-        }
-
-        self.client_user_session_auth_type()
-            .post(ep!(self, "/auth/account/change/email"))
-            .json(&ChangeEmailRequest {
-                email,
-                current_password,
-            })
-            .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -187,7 +131,7 @@ impl Http {
             captcha: Option<&'a str>,
         }
 
-        Ok(reqwest::Client::new()
+        Ok(Client::unauthenticated()
             .post(ep!(
                 api_root = "https://api.revolt.chat",
                 "/auth/session/login"
@@ -201,17 +145,66 @@ impl Http {
             })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
+}
+
+impl UserHttp {
+    // account
+    pub async fn fetch_account(&self) -> Result<Account> {
+        Ok(self
+            .client
+            .get(ep!(self, "/auth/account"))
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+
+    pub async fn change_password(&self, password: &str, current_password: &str) -> Result {
+        #[derive(serde::Serialize)]
+        struct ChangePasswordRequest<'a> {
+            password: &'a str,
+            current_password: &'a str,
+        }
+
+        self.client
+            .post(ep!(self, "/auth/account/change/password"))
+            .json(&ChangePasswordRequest {
+                password,
+                current_password,
+            })
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn change_email(&self, email: &str, current_password: &str) -> Result {
+        #[derive(serde::Serialize)]
+        struct ChangeEmailRequest<'a> {
+            email: &'a str,
+            current_password: &'a str,
+        }
+
+        self.client
+            .post(ep!(self, "/auth/account/change/email"))
+            .json(&ChangeEmailRequest {
+                email,
+                current_password,
+            })
+            .send()
+            .await?;
+
+        Ok(())
+    }
 
     pub async fn logout(self) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .delete(ep!(self, "/auth/session/logout"))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -222,44 +215,40 @@ impl Http {
             friendly_name: &'a str,
         }
 
-        self.client_user_session_auth_type()
+        self.client
             .patch(ep!(self, "/auth/session/{}" session))
             .json(&EditSessionRequest { friendly_name })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
 
     pub async fn delete_session(&self, session: SessionId) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .delete(ep!(self, "/auth/session/{}" session))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
 
     pub async fn fetch_sessions(&self) -> Result<Vec<SessionInfo>> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/auth/session/all"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
     pub async fn delete_all_sessions(&self, revoke_self: bool) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .delete(ep!(self, "/auth/session/all"))
             .query(&[("revoke_self", revoke_self)])
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
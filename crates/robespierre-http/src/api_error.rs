@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+use reqwest::{header::HeaderMap, StatusCode};
+
+use crate::ratelimit;
+
+/// An error returned by the api, parsed from the `type` field of the
+/// json body of an error response.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, thiserror::Error)]
+#[serde(tag = "type")]
+pub enum ApiError {
+    #[error("label me")]
+    LabelMe,
+
+    // onboarding
+    #[error("already onboarded")]
+    AlreadyOnboarded,
+
+    // users
+    #[error("username taken")]
+    UsernameTaken,
+    #[error("unknown user")]
+    UnknownUser,
+    #[error("already friends")]
+    AlreadyFriends,
+    #[error("already sent a friend request")]
+    AlreadySentRequest,
+    #[error("blocked")]
+    Blocked,
+    #[error("blocked by other user")]
+    BlockedByOther,
+    #[error("not friends")]
+    NotFriends,
+
+    // channels
+    #[error("unknown channel")]
+    UnknownChannel,
+    #[error("unknown attachment")]
+    UnknownAttachment,
+    #[error("unknown message")]
+    UnknownMessage,
+    #[error("cannot edit message")]
+    CannotEditMessage,
+    #[error("cannot join call")]
+    CannotJoinCall,
+    #[error("too many attachments")]
+    TooManyAttachments,
+    #[error("too many replies")]
+    TooManyReplies,
+    #[error("empty message")]
+    EmptyMessage,
+    #[error("cannot remove yourself")]
+    CannotRemoveYourself,
+    #[error("group too large (max {max:?})")]
+    GroupTooLarge {
+        #[serde(default)]
+        max: Option<usize>,
+    },
+    #[error("already in group")]
+    AlreadyInGroup,
+    #[error("not in group")]
+    NotInGroup,
+
+    // servers
+    #[error("unknown server")]
+    UnknownServer,
+    #[error("invalid role")]
+    InvalidRole,
+    #[error("banned")]
+    Banned,
+    #[error("already in server")]
+    AlreadyInServer,
+    #[error("too many servers (max {max:?})")]
+    TooManyServers {
+        #[serde(default)]
+        max: Option<usize>,
+    },
+
+    // bots
+    #[error("reached maximum number of bots")]
+    ReachedMaximumBots,
+    #[error("is bot")]
+    IsBot,
+    #[error("bot is private")]
+    BotIsPrivate,
+
+    // permissions
+    #[error("missing permission {permission:?}")]
+    MissingPermission {
+        #[serde(default)]
+        permission: Option<String>,
+    },
+    #[error("cannot give missing permissions")]
+    CannotGiveMissingPermissions,
+
+    // general
+    #[error("database error: {operation} with {with}")]
+    DatabaseError {
+        #[serde(default)]
+        operation: String,
+        #[serde(default)]
+        with: String,
+    },
+    #[error("internal error")]
+    InternalError,
+    #[error("invalid operation")]
+    InvalidOperation,
+    #[error("invalid credentials")]
+    InvalidCredentials,
+    #[error("invalid session")]
+    InvalidSession,
+    #[error("duplicate nonce")]
+    DuplicateNonce,
+    #[error("voso unavailable")]
+    VosoUnavailable,
+    #[error("not found")]
+    NotFound,
+    #[error("no effect")]
+    NoEffect,
+    #[error("failed validation: {error}")]
+    FailedValidation {
+        #[serde(default)]
+        error: serde_json::Value,
+    },
+
+    /// The request was rate limited, and the ratelimiter gave up retrying it.
+    #[serde(skip)]
+    #[error("rate limited (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// The body of the response didn't contain an error
+    /// that is known to the library.
+    #[serde(other)]
+    #[error("unknown error")]
+    Unknown,
+}
+
+impl ApiError {
+    pub(crate) fn from_response(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Self {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Self::RateLimited {
+                retry_after: ratelimit::retry_after(status, headers),
+            };
+        }
+
+        serde_json::from_slice(body).unwrap_or(Self::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_error_bodies() {
+        let headers = HeaderMap::new();
+
+        assert_eq!(
+            ApiError::from_response(
+                StatusCode::FORBIDDEN,
+                &headers,
+                br#"{"type":"MissingPermission","permission":"SendMessage"}"#
+            ),
+            ApiError::MissingPermission {
+                permission: Some("SendMessage".to_string())
+            }
+        );
+        assert_eq!(
+            ApiError::from_response(
+                StatusCode::NOT_FOUND,
+                &headers,
+                br#"{"type":"UnknownChannel"}"#
+            ),
+            ApiError::UnknownChannel
+        );
+        assert_eq!(
+            ApiError::from_response(
+                StatusCode::BAD_REQUEST,
+                &headers,
+                br#"{"type":"SomethingNew"}"#
+            ),
+            ApiError::Unknown
+        );
+        assert_eq!(
+            ApiError::from_response(StatusCode::BAD_GATEWAY, &headers, b"<html></html>"),
+            ApiError::Unknown
+        );
+        assert_eq!(
+            ApiError::from_response(StatusCode::TOO_MANY_REQUESTS, &headers, b""),
+            ApiError::RateLimited { retry_after: None }
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use std::{fmt, io, path::Path, pin::Pin, sync::Arc};
+
+use futures::TryStreamExt;
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+use robespierre_models::autumn::{
+    Attachment, AttachmentId, AttachmentTag, AutumnConfiguration, SizeOptions,
+};
+use tokio::io::AsyncRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use super::impl_prelude::*;
+use crate::{
+    request::{RequestBuilder, Route},
+    HttpError,
+};
+
+#[derive(serde::Deserialize)]
+struct AutumnUploadResponse {
+    id: AttachmentId,
+}
+
+type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+/// A file to upload to autumn with [`Http::upload_autumn_stream`],
+/// read as it is sent instead of being loaded in memory.
+pub struct AutumnUpload {
+    name: String,
+    length: u64,
+    content_type: Option<String>,
+    reader: Pin<Box<dyn AsyncRead + Send + Sync>>,
+    progress: Option<ProgressCallback>,
+}
+
+impl AutumnUpload {
+    /// Uploads `length` bytes read from `reader`, as a file called `name`.
+    pub fn from_reader(
+        name: impl Into<String>,
+        length: u64,
+        reader: impl AsyncRead + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            length,
+            content_type: None,
+            reader: Box::pin(reader),
+            progress: None,
+        }
+    }
+
+    /// Uploads the file at `path`, keeping its name.
+    pub async fn from_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let length = file.metadata().await?.len();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Self::from_reader(name, length, file))
+    }
+
+    /// Sets the content type of the file; by default it is
+    /// guessed from the extension of the name.
+    pub fn content_type(self, content_type: impl Into<String>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            ..self
+        }
+    }
+
+    /// Sets a callback called with the number of bytes sent so far,
+    /// and the total number of bytes, as the file is uploaded.
+    pub fn on_progress(self, progress: impl Fn(u64, u64) + Send + Sync + 'static) -> Self {
+        Self {
+            progress: Some(Arc::new(progress)),
+            ..self
+        }
+    }
+}
+
+impl fmt::Debug for AutumnUpload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutumnUpload")
+            .field("name", &self.name)
+            .field("length", &self.length)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+impl Http {
+    /// Uploads a file to autumn, returning the [`AttachmentId`]
+    pub async fn upload_autumn(
+        &self,
+        tag: AttachmentTag,
+        name: String,
+        bytes: Vec<u8>,
+    ) -> Result<AttachmentId> {
+        let config = self.revolt_configuration().await?;
+        let part = Part::bytes(bytes).file_name(name.clone());
+        let form = Form::new().part(name, part);
+        let req = self
+            .client
+            .post(autumn_tag_upload!(config, tag))
+            .multipart(form);
+        let resp = req.send().await?.json::<AutumnUploadResponse>().await?;
+        Ok(resp.id)
+    }
+
+    /// Uploads a file to autumn, reading it as it is sent, returning the [`AttachmentId`]
+    ///
+    /// Fails with [`HttpError::FileTooLarge`] before sending anything if the
+    /// file is larger than what the instance accepts for `tag`. The check is
+    /// skipped if the configuration of autumn cannot be fetched.
+    pub async fn upload_autumn_stream(
+        &self,
+        tag: AttachmentTag,
+        upload: AutumnUpload,
+    ) -> Result<AttachmentId> {
+        let AutumnUpload {
+            name,
+            length,
+            content_type,
+            reader,
+            progress,
+        } = upload;
+
+        self.check_autumn_upload(tag, length).await?;
+
+        let content_type = content_type.unwrap_or_else(|| {
+            mime_guess::from_path(&name)
+                .first_or_octet_stream()
+                .to_string()
+        });
+
+        let mut sent = 0;
+        let stream = ReaderStream::new(reader).inspect_ok(move |chunk| {
+            sent += chunk.len() as u64;
+            if let Some(progress) = &progress {
+                progress(sent, length);
+            }
+        });
+
+        let config = self.revolt_configuration().await?;
+        let part = Part::stream_with_length(Body::wrap_stream(stream), length)
+            .file_name(name.clone())
+            .mime_str(&content_type)?;
+        let form = Form::new().part(name, part);
+        let req = self
+            .client
+            .post(autumn_tag_upload!(config, tag))
+            .multipart(form);
+        let resp = req.send().await?.json::<AutumnUploadResponse>().await?;
+        Ok(resp.id)
+    }
+
+    /// Gets the configuration of autumn, like the maximum size of files
+    /// for each tag, fetching it the first time it is needed.
+    pub async fn autumn_configuration(&self) -> Result<Arc<AutumnConfiguration>> {
+        let config = self
+            .autumn_config
+            .get_or_try_init(|| async {
+                let config = self.revolt_configuration().await?;
+                let route = Route::new("{autumn}/", format!("{}/", config.features.autumn.url()));
+
+                Ok::<_, HttpError>(Arc::new(self.client.get(route).send().await?.json().await?))
+            })
+            .await?;
+
+        Ok(Arc::clone(config))
+    }
+
+    async fn check_autumn_upload(&self, tag: AttachmentTag, size: u64) -> Result {
+        let config = match self.autumn_configuration().await {
+            Ok(config) => config,
+            Err(e) => {
+                tracing::debug!(
+                    "Cannot get the autumn configuration ({}), skipping size check",
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        match config.tag(tag) {
+            Some(tag_config) if size > tag_config.max_size => Err(HttpError::FileTooLarge {
+                tag,
+                size,
+                max_size: tag_config.max_size,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Downloads an attachment, returning its contents
+    ///
+    /// `size` is used to resize images.
+    pub async fn download_attachment(
+        &self,
+        attachment: &Attachment,
+        size: Option<SizeOptions>,
+    ) -> Result<Vec<u8>> {
+        Ok(self
+            .download_request(attachment, size)
+            .await?
+            .send()
+            .await?
+            .bytes()
+            .await?
+            .to_vec())
+    }
+
+    /// Downloads an attachment, returning a reader over its contents,
+    /// without loading the whole file in memory.
+    ///
+    /// `size` is used to resize images.
+    pub async fn download_attachment_stream(
+        &self,
+        attachment: &Attachment,
+        size: Option<SizeOptions>,
+    ) -> Result<impl AsyncRead + Send + Unpin> {
+        let stream = self
+            .download_request(attachment, size)
+            .await?
+            .send()
+            .await?
+            .bytes_stream()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e));
+
+        Ok(StreamReader::new(stream))
+    }
+
+    async fn download_request(
+        &self,
+        attachment: &Attachment,
+        size: Option<SizeOptions>,
+    ) -> Result<RequestBuilder> {
+        let config = self.revolt_configuration().await?;
+        let route = Route::new("{autumn}/{}/{}", attachment.url(&config, size.as_ref()));
+
+        Ok(self.client.get(route))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use reqwest::{Request, Response};
+
+    use super::*;
+    use crate::{transport::Transport, HttpAuthentication, RetryPolicy};
+
+    /// Serves the configurations of revolt and autumn, and accepts uploads.
+    #[derive(Default)]
+    struct Configs {
+        autumn_unreachable: bool,
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Transport for Configs {
+        async fn execute(&self, request: Request) -> Result<Response> {
+            let url = request.url().to_string();
+            self.urls.lock().unwrap().push(url.clone());
+
+            let (status, body) = match url.as_str() {
+                "https://revolt.test/" => (
+                    200,
+                    r#"{
+                        "revolt": "0.5.3",
+                        "features": {
+                            "captcha": { "enabled": false, "key": "" },
+                            "email": false,
+                            "invite_only": false,
+                            "autumn": { "enabled": true, "url": "https://autumn.test" },
+                            "january": { "enabled": true, "url": "https://january.test" },
+                            "voso": { "enabled": false, "url": "", "ws": "" }
+                        },
+                        "ws": "wss://ws.revolt.test",
+                        "app": "https://app.revolt.test",
+                        "vapid": ""
+                    }"#,
+                ),
+                "https://autumn.test/" if self.autumn_unreachable => (502, ""),
+                "https://autumn.test/" => (
+                    200,
+                    r#"{
+                        "autumn": "1.1.5",
+                        "tags": { "attachments": { "max_size": 16, "enabled": true } },
+                        "jpeg_quality": 80
+                    }"#,
+                ),
+                "https://autumn.test/attachments" => (200, r#"{"id":"uploaded"}"#),
+                _ => panic!("unexpected request to {}", url),
+            };
+
+            Ok(http::Response::builder()
+                .status(status)
+                .body(body)
+                .unwrap()
+                .into())
+        }
+    }
+
+    fn http(transport: &Arc<Configs>) -> Http {
+        Http::builder()
+            .api_root("https://revolt.test")
+            .transport(Arc::clone(transport))
+            .retry_policy(RetryPolicy::none())
+            .build_lazy(HttpAuthentication::BotToken { token: "token" })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn oversized_uploads_are_not_sent() {
+        let transport = Arc::new(Configs::default());
+        let http = http(&transport);
+
+        let upload = AutumnUpload::from_reader("log.txt", 32, &[0u8; 32][..]);
+        let err = http
+            .upload_autumn_stream(AttachmentTag::Attachments, upload)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            HttpError::FileTooLarge {
+                size: 32,
+                max_size: 16,
+                ..
+            }
+        ));
+        assert_eq!(
+            *transport.urls.lock().unwrap(),
+            vec!["https://revolt.test/", "https://autumn.test/"]
+        );
+    }
+
+    #[tokio::test]
+    async fn uploads_without_autumn_configuration() {
+        let transport = Arc::new(Configs {
+            autumn_unreachable: true,
+            ..Default::default()
+        });
+        let http = http(&transport);
+
+        let upload = AutumnUpload::from_reader("log.txt", 32, &[0u8; 32][..]);
+        let id = http
+            .upload_autumn_stream(AttachmentTag::Attachments, upload)
+            .await
+            .unwrap();
+
+        assert_eq!(id.as_ref(), "uploaded");
+        assert_eq!(
+            *transport.urls.lock().unwrap(),
+            vec![
+                "https://revolt.test/",
+                "https://autumn.test/",
+                "https://autumn.test/attachments"
+            ]
+        );
+    }
+}
@@ -6,7 +6,7 @@ use robespierre_models::{
 
 use super::impl_prelude::*;
 
-impl Http {
+impl UserHttp {
     pub async fn create_bot(&self, name: &str) -> Result<Bot> {
         #[derive(serde::Serialize)]
         struct CreateBotRequest<'a> {
@@ -14,34 +14,31 @@ impl Http {
         }
 
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .post(ep!(self, "/bots/create"))
             .json(&CreateBotRequest { name })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
     pub async fn fetch_owned_bots(&self) -> Result<FetchOwnedBotsResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/bots/@me"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
     pub async fn fetch_bot(&self, bot: UserId) -> Result<FetchBotResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/bots/{}" bot))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -66,7 +63,7 @@ impl Http {
             remove: Option<BotField>,
         }
 
-        self.client_user_session_auth_type()
+        self.client
             .patch(ep!(self, "/bots/{}" bot))
             .json(&EditBotRequest {
                 name,
@@ -75,39 +72,32 @@ impl Http {
                 remove,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
         Ok(())
     }
 
     pub async fn delete_bot(&self, bot: UserId) -> Result {
-        self.client_user_session_auth_type()
-            .delete(ep!(self, "/bots/{}" bot))
-            .send()
-            .await?
-            .error_for_status()?;
+        self.client.delete(ep!(self, "/bots/{}" bot)).send().await?;
 
         Ok(())
     }
 
     pub async fn fetch_public_bot(&self, bot: UserId) -> Result<PublicBot> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/bots/{}/invite" bot))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
     pub async fn invite_bot(&self, bot: UserId, target: InviteBotTarget) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .post(ep!(self, "/bots/{}/invite" bot))
             .json(&target)
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
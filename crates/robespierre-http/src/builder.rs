@@ -0,0 +1,274 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use reqwest::{Certificate, Proxy};
+
+use robespierre_models::core::RevoltConfiguration;
+
+use crate::{
+    core::SharedRevoltConfiguration, request::Client, AuthType, Http, HttpAuthentication,
+    Interceptor, Result, RetryPolicy, Transport,
+};
+
+/// A builder for [`Http`], to configure the underlying http client.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use robespierre_http::{Http, HttpAuthentication, reqwest::Proxy};
+/// # async fn f() -> robespierre_http::Result {
+/// let http = Http::builder()
+///     .api_root("https://revolt.example.com/api")
+///     .timeout(Duration::from_secs(30))
+///     .user_agent("my-bot/1.0")
+///     .proxy(Proxy::all("http://proxy.example.com:3128")?)
+///     .build(HttpAuthentication::BotToken { token: "..." })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct HttpBuilder {
+    api_root: String,
+    client: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    interceptors: Vec<Box<dyn Interceptor>>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxies: Vec<Proxy>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    root_certificates: Vec<Certificate>,
+    retry_policy: RetryPolicy,
+}
+
+impl fmt::Debug for HttpBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpBuilder")
+            .field("api_root", &self.api_root)
+            .field("client", &self.client)
+            .field("transport", &self.transport.as_ref().map(|_| ".."))
+            .field("interceptors", &self.interceptors.len())
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("user_agent", &self.user_agent)
+            .field("proxies", &self.proxies)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("root_certificates", &self.root_certificates)
+            .field("retry_policy", &self.retry_policy)
+            .finish()
+    }
+}
+
+impl Default for HttpBuilder {
+    fn default() -> Self {
+        Self {
+            api_root: "https://api.revolt.chat".to_string(),
+            client: None,
+            transport: None,
+            interceptors: vec![],
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            proxies: vec![],
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            root_certificates: vec![],
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl HttpBuilder {
+    /// Creates a new builder, for the official revolt instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the url of the api.
+    ///
+    /// Use this if using a self hosted instance of revolt.
+    pub fn api_root(self, api_root: impl Into<String>) -> Self {
+        Self {
+            api_root: api_root.into(),
+            ..self
+        }
+    }
+
+    /// Uses an already built [`reqwest::Client`].
+    ///
+    /// The other options that configure the client (timeouts, user agent, proxies,
+    /// connection pool and root certificates) are ignored when this is set.
+    pub fn client(self, client: reqwest::Client) -> Self {
+        Self {
+            client: Some(client),
+            ..self
+        }
+    }
+
+    /// Sends the requests through `transport`, instead of over the network.
+    ///
+    /// The requests are still built with the [`reqwest::Client`], but the options
+    /// that affect how they are sent (timeouts, proxies, connection pool) have no effect.
+    pub fn transport(self, transport: impl Transport) -> Self {
+        Self {
+            transport: Some(Arc::new(transport)),
+            ..self
+        }
+    }
+
+    /// Adds an [`Interceptor`], called around every request.
+    ///
+    /// Interceptors are called in the order they were added.
+    pub fn interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// Sets the timeout for whole requests, from connecting until the response body is read.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sets the timeout for connecting.
+    pub fn connect_timeout(self, connect_timeout: Duration) -> Self {
+        Self {
+            connect_timeout: Some(connect_timeout),
+            ..self
+        }
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    pub fn user_agent(self, user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: Some(user_agent.into()),
+            ..self
+        }
+    }
+
+    /// Adds a proxy to route requests through.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Sets the maximum number of idle connections kept per host.
+    pub fn pool_max_idle_per_host(self, max: usize) -> Self {
+        Self {
+            pool_max_idle_per_host: Some(max),
+            ..self
+        }
+    }
+
+    /// Sets how long idle connections are kept in the pool.
+    pub fn pool_idle_timeout(self, timeout: Duration) -> Self {
+        Self {
+            pool_idle_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Adds a trusted root certificate, like the one of a private CA.
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Sets the [`RetryPolicy`]; defaults to [`RetryPolicy::default`].
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    fn build_client(&mut self) -> Result<reqwest::Client> {
+        if let Some(client) = self.client.take() {
+            return Ok(client);
+        }
+
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(user_agent) = self.user_agent.take() {
+            builder = builder.user_agent(user_agent);
+        }
+        for proxy in self.proxies.drain(..) {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        for certificate in self.root_certificates.drain(..) {
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    fn build_http(
+        mut self,
+        auth: HttpAuthentication,
+        revolt_config: Option<RevoltConfiguration>,
+    ) -> Result<Http> {
+        let client = self.build_client()?;
+        let transport = match self.transport.take() {
+            Some(transport) => transport,
+            None => Arc::new(client.clone()),
+        };
+        let client = Client::new(
+            client,
+            transport,
+            std::mem::take(&mut self.interceptors),
+            Some(auth.header()?),
+            self.retry_policy,
+        );
+        let auth_type = match auth {
+            HttpAuthentication::BotToken { .. } => AuthType::Bot,
+            HttpAuthentication::UserSession { .. } => AuthType::UserSession,
+        };
+
+        Ok(Http {
+            client,
+            api_root: self.api_root,
+            revolt_config: SharedRevoltConfiguration::new(revolt_config),
+            autumn_config: Arc::default(),
+            auth_type,
+        })
+    }
+
+    /// Builds the [`Http`], fetching the configuration of the revolt instance.
+    pub async fn build<'auth>(self, auth: impl Into<HttpAuthentication<'auth>>) -> Result<Http> {
+        let http = self.build_lazy(auth)?;
+        http.refresh_revolt_configuration().await?;
+
+        Ok(http)
+    }
+
+    /// Builds the [`Http`] with an already known configuration of the
+    /// revolt instance, without making any requests.
+    pub fn build_with_configuration<'auth>(
+        self,
+        auth: impl Into<HttpAuthentication<'auth>>,
+        revolt_config: RevoltConfiguration,
+    ) -> Result<Http> {
+        self.build_http(auth.into(), Some(revolt_config))
+    }
+
+    /// Builds the [`Http`] without making any requests; the configuration
+    /// of the revolt instance is fetched the first time it is needed.
+    ///
+    /// See [`Http::revolt_configuration`] and [`Http::refresh_revolt_configuration`].
+    pub fn build_lazy<'auth>(self, auth: impl Into<HttpAuthentication<'auth>>) -> Result<Http> {
+        self.build_http(auth.into(), None)
+    }
+}
@@ -16,7 +16,6 @@ impl Http {
             .get(ep!(self, "/channels/{}" channel_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -55,8 +54,7 @@ impl Http {
                 remove,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -66,8 +64,7 @@ impl Http {
         self.client
             .delete(ep!(self, "/channels/{}" channel_id))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -82,7 +79,6 @@ impl Http {
             .post(ep!(self, "/channels/{}/invites" channel_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -98,8 +94,7 @@ impl Http {
             .put(ep!(self, "/channels/{}/permissions/{}" channel_id, role_id))
             .json(&PermissionsUpdateRequest { permissions })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -114,8 +109,7 @@ impl Http {
             .put(ep!(self, "/channels/{}/permissions/default" channel_id))
             .json(&PermissionsUpdateRequest { permissions })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -1,18 +1,156 @@
+use std::sync::{Arc, RwLock};
+
 use robespierre_models::core::RevoltConfiguration;
 
-use crate::{Http, Result};
+use crate::{
+    request::{Client, Route},
+    Http, Result,
+};
+
+/// The [`RevoltConfiguration`] of an [`Http`], shared between its clones.
+///
+/// Empty until fetched, if it wasn't known when the [`Http`] was created.
+#[derive(Clone, Default)]
+pub(crate) struct SharedRevoltConfiguration(Arc<RwLock<Option<Arc<RevoltConfiguration>>>>);
+
+impl SharedRevoltConfiguration {
+    pub(crate) fn new(config: Option<RevoltConfiguration>) -> Self {
+        Self(Arc::new(RwLock::new(config.map(Arc::new))))
+    }
+
+    fn get(&self) -> Option<Arc<RevoltConfiguration>> {
+        self.0.read().unwrap().clone()
+    }
+
+    fn set(&self, config: RevoltConfiguration) -> Arc<RevoltConfiguration> {
+        let config = Arc::new(config);
+        *self.0.write().unwrap() = Some(Arc::clone(&config));
+        config
+    }
+}
 
 impl Http {
     pub(crate) async fn get_revolt_config(
-        client: &reqwest::Client,
+        client: &Client,
         root_url: &str,
     ) -> Result<RevoltConfiguration> {
         Ok(client
-            .get(root_url)
+            .get(Route::new("/", root_url.to_string()))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
+
+    /// Gets the configuration of the revolt instance, fetching it
+    /// if it isn't known yet.
+    pub async fn revolt_configuration(&self) -> Result<Arc<RevoltConfiguration>> {
+        match self.revolt_config.get() {
+            Some(config) => Ok(config),
+            None => self.refresh_revolt_configuration().await,
+        }
+    }
+
+    /// Fetches the configuration of the revolt instance again, replacing
+    /// the known one.
+    pub async fn refresh_revolt_configuration(&self) -> Result<Arc<RevoltConfiguration>> {
+        let config = Self::get_revolt_config(&self.client, &self.api_root).await?;
+
+        Ok(self.revolt_config.set(config))
+    }
+
+    /// Gets the configuration of the revolt instance, if it is known,
+    /// without fetching it.
+    pub fn get_revolt_configuration(&self) -> Option<Arc<RevoltConfiguration>> {
+        self.revolt_config.get()
+    }
+
+    /// Gets the websocket url
+    pub async fn get_ws_url(&self) -> Result<String> {
+        Ok(self.revolt_configuration().await?.ws.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use reqwest::{Request, Response};
+
+    use super::*;
+    use crate::{transport::Transport, HttpAuthentication, RetryPolicy};
+
+    const CONFIG: &str = r#"{
+        "revolt": "0.5.3",
+        "features": {
+            "captcha": { "enabled": false, "key": "" },
+            "email": false,
+            "invite_only": false,
+            "autumn": { "enabled": true, "url": "https://autumn.revolt.chat" },
+            "january": { "enabled": true, "url": "https://jan.revolt.chat" },
+            "voso": { "enabled": true, "url": "https://voso.revolt.chat", "ws": "wss://voso.revolt.chat/ws" }
+        },
+        "ws": "wss://ws.revolt.chat",
+        "app": "https://app.revolt.chat",
+        "vapid": ""
+    }"#;
+
+    /// Serves the configuration of revolt.
+    #[derive(Default)]
+    struct Config {
+        urls: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Transport for Config {
+        async fn execute(&self, request: Request) -> Result<Response> {
+            let url = request.url().to_string();
+            assert_eq!(url, "https://revolt.test/");
+            self.urls.lock().unwrap().push(url);
+
+            Ok(http::Response::builder()
+                .status(200)
+                .body(CONFIG)
+                .unwrap()
+                .into())
+        }
+    }
+
+    #[tokio::test]
+    async fn known_configuration_makes_no_requests() {
+        let config: RevoltConfiguration = serde_json::from_str(CONFIG).unwrap();
+        // round-trips, so that it can be stored and reused
+        let config: RevoltConfiguration =
+            serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+
+        let transport = Arc::new(Config::default());
+        let http = Http::builder()
+            .api_root("https://revolt.test")
+            .transport(Arc::clone(&transport))
+            .build_with_configuration(HttpAuthentication::BotToken { token: "token" }, config)
+            .unwrap();
+
+        assert!(http.get_revolt_configuration().is_some());
+        assert_eq!(http.get_ws_url().await.unwrap(), "wss://ws.revolt.chat");
+        assert!(transport.urls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn lazy_configuration_is_fetched_once_when_needed() {
+        let transport = Arc::new(Config::default());
+        let http = Http::builder()
+            .api_root("https://revolt.test")
+            .transport(Arc::clone(&transport))
+            .retry_policy(RetryPolicy::none())
+            .build_lazy(HttpAuthentication::BotToken { token: "token" })
+            .unwrap();
+        assert!(http.get_revolt_configuration().is_none());
+        assert!(transport.urls.lock().unwrap().is_empty());
+
+        assert_eq!(http.get_ws_url().await.unwrap(), "wss://ws.revolt.chat");
+        assert!(http.get_revolt_configuration().is_some());
+        assert_eq!(http.get_ws_url().await.unwrap(), "wss://ws.revolt.chat");
+        assert_eq!(transport.urls.lock().unwrap().len(), 1);
+    }
 }
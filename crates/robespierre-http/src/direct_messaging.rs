@@ -13,7 +13,6 @@ impl Http {
             .get(ep!(self, "/users/dms"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -25,7 +24,6 @@ impl Http {
             .get(ep!(self, "/users/{}/dm" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -30,6 +30,7 @@ impl Http {
         Ok(self
             .client
             .post(ep!(self, "/channels/create"))
+            .idempotent(!nonce.is_empty())
             .json(&CreateGroupRequest {
                 name,
                 description,
@@ -39,7 +40,6 @@ impl Http {
             })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -51,11 +51,90 @@ impl Http {
             .get(ep!(self, "/channels/{}/members" group))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
-    // TODO: add member to group
-    // TODO: remove member from group
+    /// Adds a member to a group
+    pub async fn add_group_member(&self, group: ChannelId, user: UserId) -> Result {
+        self.client
+            .put(ep!(self, "/channels/{}/recipients/{}" group, user))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes a member from a group
+    pub async fn remove_group_member(&self, group: ChannelId, user: UserId) -> Result {
+        self.client
+            .delete(ep!(self, "/channels/{}/recipients/{}" group, user))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Makes another member the owner of a group
+    pub async fn transfer_group_ownership(&self, group: ChannelId, owner: UserId) -> Result {
+        #[derive(serde::Serialize)]
+        struct TransferOwnershipRequest {
+            owner: UserId,
+        }
+
+        self.client
+            .patch(ep!(self, "/channels/{}" group))
+            .json(&TransferOwnershipRequest { owner })
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::Method;
+
+    use super::*;
+    use crate::test_utils::{mock_http, MockTransport};
+
+    #[tokio::test]
+    async fn manages_group_members() {
+        let transport = Arc::new(MockTransport::default());
+        for _ in 0..3 {
+            transport.respond(204, "");
+        }
+
+        let http = mock_http(&transport);
+        let group: ChannelId = "01FE9XVK2GXQXHZ5BCZBD6PA8P".parse().unwrap();
+        let user: UserId = "01FE9XNFEDH6NSPKRQY5SVXYRP".parse().unwrap();
+
+        http.add_group_member(group, user).await.unwrap();
+        http.remove_group_member(group, user).await.unwrap();
+        http.transfer_group_ownership(group, user).await.unwrap();
+
+        let recipient = "https://revolt.test/channels/01FE9XVK2GXQXHZ5BCZBD6PA8P/recipients/01FE9XNFEDH6NSPKRQY5SVXYRP";
+        let requests = transport.requests.lock().unwrap().clone();
+        assert_eq!(
+            requests[..2],
+            [
+                (Method::PUT, recipient.to_string(), None),
+                (Method::DELETE, recipient.to_string(), None),
+            ]
+        );
+
+        let (method, url, _) = &requests[2];
+        assert_eq!(method, Method::PATCH);
+        assert_eq!(
+            url,
+            "https://revolt.test/channels/01FE9XVK2GXQXHZ5BCZBD6PA8P"
+        );
+        assert_eq!(
+            transport.json_body(2),
+            serde_json::json!({ "owner": "01FE9XNFEDH6NSPKRQY5SVXYRP" })
+        );
+    }
 }
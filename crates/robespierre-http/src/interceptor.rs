@@ -0,0 +1,231 @@
+//! Hooks to observe and modify the requests made by an [`crate::Http`].
+
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{Method, Request, Response};
+
+use crate::Result;
+
+/// Information about a request that is about to be sent.
+#[derive(Debug, Clone, Copy)]
+pub struct RequestInfo<'a> {
+    /// The method of the request.
+    pub method: &'a Method,
+    /// The template of the route, like `/channels/{}/messages`.
+    pub route: &'static str,
+    /// How many times the request has been sent, including this one.
+    /// Retries (including after being rate limited) go through the
+    /// interceptors again.
+    pub attempt: u32,
+}
+
+/// Information about the result of sending a request.
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseInfo<'a> {
+    /// The method of the request.
+    pub method: &'a Method,
+    /// The template of the route, like `/channels/{}/messages`.
+    pub route: &'static str,
+    /// How many times the request has been sent, including this one.
+    pub attempt: u32,
+    /// How long it took to get the response, or the error.
+    pub elapsed: Duration,
+}
+
+/// A hook called around every request sent by an [`crate::Http`],
+/// added with [`crate::HttpBuilder::interceptor`].
+///
+/// Interceptors are called in the order they were added.
+///
+/// ```
+/// # use robespierre_http::{interceptor::{Interceptor, ResponseInfo}, reqwest::Response, Http, Result};
+/// struct LogLatency;
+///
+/// impl Interceptor for LogLatency {
+///     fn after_receive(&self, response: &mut Result<Response>, info: &ResponseInfo<'_>) {
+///         let status = response.as_ref().map(Response::status);
+///         println!("{} {} -> {:?} in {:?}", info.method, info.route, status, info.elapsed);
+///     }
+/// }
+///
+/// let builder = Http::builder().interceptor(LogLatency);
+/// ```
+pub trait Interceptor: Send + Sync + 'static {
+    /// Called right before a request is handed to the transport; it can be
+    /// modified, for example to add headers.
+    fn before_send(&self, request: &mut Request, info: &RequestInfo<'_>) {
+        let _ = (request, info);
+    }
+
+    /// Called after the transport returned, before the status of
+    /// the response is checked; the response (or the error) can be
+    /// modified, or replaced.
+    fn after_receive(&self, response: &mut Result<Response>, info: &ResponseInfo<'_>) {
+        let _ = (response, info);
+    }
+}
+
+/// Lets the caller keep a handle to the interceptor, to inspect it later.
+impl<T: Interceptor + ?Sized> Interceptor for Arc<T> {
+    fn before_send(&self, request: &mut Request, info: &RequestInfo<'_>) {
+        T::before_send(self, request, info)
+    }
+
+    fn after_receive(&self, response: &mut Result<Response>, info: &ResponseInfo<'_>) {
+        T::after_receive(self, response, info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    };
+
+    use async_trait::async_trait;
+    use reqwest::{header::HeaderValue, StatusCode};
+    use robespierre_models::id::{ChannelId, MessageId};
+
+    use super::*;
+    use crate::{transport::Transport, Http, HttpAuthentication, RetryPolicy};
+
+    fn with_status(status: u16) -> Response {
+        http::Response::builder()
+            .status(status)
+            .header("x-ratelimit-reset-after", "10")
+            .body("")
+            .unwrap()
+            .into()
+    }
+
+    /// Answers `204` if the request has the `x-test` header, and `400` otherwise.
+    struct EchoHeader;
+
+    #[async_trait]
+    impl Transport for EchoHeader {
+        async fn execute(&self, request: Request) -> Result<Response> {
+            match request.headers().get("x-test") {
+                Some(_) => Ok(with_status(204)),
+                None => Ok(with_status(400)),
+            }
+        }
+    }
+
+    /// Answers `429` to the first request, and `204` to the others.
+    #[derive(Default)]
+    struct RatelimitedOnce(AtomicBool);
+
+    #[async_trait]
+    impl Transport for RatelimitedOnce {
+        async fn execute(&self, _request: Request) -> Result<Response> {
+            match self.0.swap(true, Ordering::SeqCst) {
+                false => Ok(with_status(429)),
+                true => Ok(with_status(204)),
+            }
+        }
+    }
+
+    /// The method, route, attempt and status of a response.
+    type Seen = (Method, &'static str, u32, Option<StatusCode>);
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<Seen>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn before_send(&self, request: &mut Request, _info: &RequestInfo<'_>) {
+            request
+                .headers_mut()
+                .insert("x-test", HeaderValue::from_static("1"));
+        }
+
+        fn after_receive(&self, response: &mut Result<Response>, info: &ResponseInfo<'_>) {
+            self.seen.lock().unwrap().push((
+                info.method.clone(),
+                info.route,
+                info.attempt,
+                response.as_ref().ok().map(Response::status),
+            ));
+        }
+    }
+
+    /// Turns `400 Bad Request` into `204 No Content`.
+    struct Forgiving;
+
+    impl Interceptor for Forgiving {
+        fn after_receive(&self, response: &mut Result<Response>, _info: &ResponseInfo<'_>) {
+            if matches!(response, Ok(r) if r.status() == StatusCode::BAD_REQUEST) {
+                *response = Ok(with_status(204));
+            }
+        }
+    }
+
+    fn http(transport: impl Transport, interceptor: impl Interceptor) -> Http {
+        Http::builder()
+            .api_root("https://revolt.test")
+            .transport(transport)
+            .interceptor(interceptor)
+            .retry_policy(RetryPolicy::none())
+            .build_lazy(HttpAuthentication::BotToken { token: "token" })
+            .unwrap()
+    }
+
+    fn ids() -> (ChannelId, MessageId) {
+        (
+            "01FE9XVK2GXQXHZ5BCZBD6PA8P".parse().unwrap(),
+            "01FE9XXP4NWJ6S2D4N7WX6W3AJ".parse().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn interceptors_see_every_request() {
+        let recorder = Arc::new(Recorder::default());
+        let http = http(EchoHeader, Arc::clone(&recorder));
+
+        let (channel, message) = ids();
+        http.delete_message(channel, message).await.unwrap();
+
+        assert_eq!(
+            *recorder.seen.lock().unwrap(),
+            vec![(
+                Method::DELETE,
+                "/channels/{}/messages/{}",
+                1,
+                Some(StatusCode::NO_CONTENT)
+            )]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ratelimited_retries_count_as_attempts() {
+        let recorder = Arc::new(Recorder::default());
+        let http = http(RatelimitedOnce::default(), Arc::clone(&recorder));
+
+        let (channel, message) = ids();
+        http.delete_message(channel, message).await.unwrap();
+
+        let route = "/channels/{}/messages/{}";
+        assert_eq!(
+            *recorder.seen.lock().unwrap(),
+            vec![
+                (
+                    Method::DELETE,
+                    route,
+                    1,
+                    Some(StatusCode::TOO_MANY_REQUESTS)
+                ),
+                (Method::DELETE, route, 2, Some(StatusCode::NO_CONTENT)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn interceptors_can_replace_responses() {
+        let http = http(EchoHeader, Forgiving);
+
+        let (channel, message) = ids();
+        http.delete_message(channel, message).await.unwrap();
+    }
+}
@@ -9,31 +9,30 @@ impl Http {
             .get(ep!(self, "/invites/{}" invite))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
+    pub async fn delete_invite(&self, invite: &str) -> Result {
+        self.client
+            .delete(ep!(self, "/invites/{}" invite))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl UserHttp {
     pub async fn join_invite(&self, invite: &str) -> Result<JoinInviteResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .post(ep!(self, "/invites/{}" invite))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
-
-    pub async fn delete_invite(&self, invite: &str) -> Result {
-        self.client
-            .delete(ep!(self, "/invites/{}" invite))
-            .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
-    }
 }
 
 #[derive(serde::Deserialize)]
@@ -0,0 +1,35 @@
+use robespierre_models::january::Embed;
+
+use super::impl_prelude::*;
+use crate::request::Route;
+
+impl Http {
+    /// Generates the embed january would show for `url`, without posting it
+    pub async fn january_embed(&self, url: &str) -> Result<Embed> {
+        let config = self.revolt_configuration().await?;
+        let route = Route::new(
+            "{january}/embed",
+            format!("{}/embed", config.features.january.url()),
+        );
+
+        Ok(self
+            .client
+            .get(route)
+            .query(&[("url", url)])
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// Gets the url of `image_url` proxied through january
+    pub async fn january_proxy_url(&self, image_url: &str) -> Result<String> {
+        let config = self.revolt_configuration().await?;
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/proxy", config.features.january.url()),
+            &[("url", image_url)],
+        )?;
+
+        Ok(url.into())
+    }
+}
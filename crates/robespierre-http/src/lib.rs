@@ -1,16 +1,13 @@
-use std::{ops::Deref, result::Result as StdResult};
+use std::{ops::Deref, result::Result as StdResult, sync::Arc};
 
-use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    multipart::{Form, Part},
-    RequestBuilder,
-};
+use reqwest::header::{HeaderName, HeaderValue, InvalidHeaderValue};
 use robespierre_models::{
     auth::Session,
-    autumn::{AttachmentId, AttachmentTag},
+    autumn::{AttachmentTag, AutumnConfiguration},
     core::RevoltConfiguration,
     id::UserId,
 };
+use tokio::sync::OnceCell;
 
 /// Any error that can happen while requesting / decoding
 #[derive(Debug, thiserror::Error)]
@@ -20,6 +17,49 @@ pub enum HttpError {
 
     #[error("decoding: {0}")]
     Decoding(#[from] serde_json::Error),
+
+    #[error("api error ({status}): {error}")]
+    Api { status: StatusCode, error: ApiError },
+
+    #[error("invalid header value: {0}")]
+    InvalidHeader(#[from] InvalidHeaderValue),
+
+    #[error("invalid url: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+
+    #[error("file too large for {tag}: {size} bytes, the maximum is {max_size}")]
+    FileTooLarge {
+        tag: AttachmentTag,
+        size: u64,
+        max_size: u64,
+    },
+
+    /// An error from a custom [`transport::Transport`].
+    #[error("transport: {0}")]
+    Transport(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl HttpError {
+    /// Gets the status code of the response, if the error came from one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Reqwest(e) => e.status(),
+            Self::Decoding(_)
+            | Self::InvalidHeader(_)
+            | Self::InvalidUrl(_)
+            | Self::FileTooLarge { .. }
+            | Self::Transport(_) => None,
+            Self::Api { status, .. } => Some(*status),
+        }
+    }
+
+    /// Gets the error the api responded with, if any.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Self::Api { error, .. } => Some(error),
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T = ()> = StdResult<T, HttpError>;
@@ -30,33 +70,18 @@ pub enum HttpAuthentication<'a> {
     BotToken { token: &'a str },
     UserSession { session_token: &'a str },
 }
-trait AuthExt: Sized {
-    fn auth(self, auth: &HttpAuthentication) -> Self;
-}
-
-impl AuthExt for RequestBuilder {
-    fn auth(self, auth: &HttpAuthentication) -> Self {
-        match auth {
-            HttpAuthentication::BotToken { token } => self.header("x-bot-token", *token),
-            HttpAuthentication::UserSession { session_token } => {
-                self.header("x-session-token", *session_token)
-            }
-        }
-    }
-}
 
-impl AuthExt for HeaderMap {
-    fn auth(mut self, auth: &HttpAuthentication) -> Self {
-        match auth {
-            HttpAuthentication::BotToken { token } => {
-                self.insert("x-bot-token", token.parse().unwrap());
-            }
-            HttpAuthentication::UserSession { session_token } => {
-                self.insert("x-session-token", session_token.parse().unwrap());
-            }
-        }
+impl HttpAuthentication<'_> {
+    /// The header to authenticate requests with.
+    fn header(&self) -> Result<(HeaderName, HeaderValue)> {
+        let (name, value) = match self {
+            Self::BotToken { token } => ("x-bot-token", token),
+            Self::UserSession { session_token } => ("x-session-token", session_token),
+        };
+        let mut value = HeaderValue::from_str(value)?;
+        value.set_sensitive(true);
 
-        self
+        Ok((HeaderName::from_static(name), value))
     }
 }
 
@@ -68,17 +93,20 @@ pub enum AuthType {
 
 macro_rules! ep {
     ($self:ident, $ep:literal $($args:tt)*) => {
-        format!(concat!("{}", $ep), $self.api_root, $($args)*)
+        $crate::request::Route::new($ep, format!(concat!("{}", $ep), $self.api_root, $($args)*))
     };
 
     (api_root = $api_root:expr, $ep:literal $($args:tt)*) => {
-        format!(concat!("{}", $ep), $api_root, $($args)*)
+        $crate::request::Route::new($ep, format!(concat!("{}", $ep), $api_root, $($args)*))
     };
 }
 
 macro_rules! autumn_tag_upload {
-    ($self:expr, $tag:expr) => {
-        format!("{}/{}", $self.revolt_config.features.autumn.url(), $tag)
+    ($config:expr, $tag:expr) => {
+        $crate::request::Route::new(
+            "{autumn}/{}",
+            format!("{}/{}", $config.features.autumn.url(), $tag),
+        )
     };
 }
 
@@ -91,14 +119,62 @@ impl<'a> From<&'a Session> for HttpAuthentication<'a> {
 }
 
 /// An instance of a client to the REST API
+#[derive(Clone)]
 pub struct Http {
-    client: reqwest::Client,
+    client: request::Client,
     api_root: String,
-    revolt_config: RevoltConfiguration,
+    revolt_config: core::SharedRevoltConfiguration,
+    autumn_config: Arc<OnceCell<Arc<AutumnConfiguration>>>,
 
     auth_type: AuthType,
 }
 
+/// A client to the REST API authenticated with a user session, which can also
+/// use the routes that are not available to bots, like [`UserHttp::fetch_account`].
+///
+/// Derefs to [`Http`], for the routes available to everyone.
+#[derive(Clone)]
+pub struct UserHttp(Http);
+
+impl UserHttp {
+    /// Creates a new client from a session.
+    pub async fn new(session: &Session) -> Result<Self> {
+        Self::new_with_url(session, "https://api.revolt.chat").await
+    }
+
+    /// Creates a new client from a session and url.
+    ///
+    /// Use this if using a self hosted instance of revolt, otherwise use [`Self::new`].
+    pub async fn new_with_url(session: &Session, api_root: &str) -> Result<Self> {
+        Ok(Self(Http::new_with_url(session, api_root).await?))
+    }
+
+    /// Gets the [`Http`] client, which can only use the routes available to everyone.
+    pub fn into_inner(self) -> Http {
+        self.0
+    }
+}
+
+impl Deref for UserHttp {
+    type Target = Http;
+
+    fn deref(&self) -> &Http {
+        &self.0
+    }
+}
+
+impl AsRef<Http> for UserHttp {
+    fn as_ref(&self) -> &Http {
+        &self.0
+    }
+}
+
+impl From<UserHttp> for Http {
+    fn from(http: UserHttp) -> Self {
+        http.0
+    }
+}
+
 pub mod core;
 
 pub mod onboarding;
@@ -125,6 +201,10 @@ pub mod groups;
 
 pub mod voice;
 
+pub mod autumn;
+
+pub mod january;
+
 pub mod server_information;
 
 pub mod server_members;
@@ -139,9 +219,35 @@ pub mod sync;
 
 pub mod web_push;
 
+pub mod ratelimit;
+
+pub mod api_error;
+
+pub mod retry;
+
+pub mod builder;
+
+pub mod transport;
+
+pub mod interceptor;
+
+mod request;
+
+#[cfg(test)]
+mod test_utils;
+
+pub use api_error::ApiError;
+pub use builder::HttpBuilder;
+pub use interceptor::Interceptor;
+pub use reqwest;
+pub use reqwest::StatusCode;
+pub use retry::RetryPolicy;
+pub use transport::Transport;
+
 mod impl_prelude {
     pub use super::Http;
     pub use super::Result;
+    pub use super::UserHttp;
 }
 
 impl Http {
@@ -157,71 +263,68 @@ impl Http {
         auth: impl Into<HttpAuthentication<'auth>>,
         api_root: &str,
     ) -> Result<Self> {
-        let auth = auth.into();
-        let mut default_headers = HeaderMap::new().auth(&auth);
-        default_headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static("*/*"));
-        let client = reqwest::Client::builder()
-            .default_headers(default_headers)
-            .build()
-            .unwrap();
-        let revolt_config = Self::get_revolt_config(&client, api_root).await?;
-        let auth_type = match auth {
-            HttpAuthentication::BotToken { .. } => AuthType::Bot,
-            HttpAuthentication::UserSession { .. } => AuthType::UserSession,
-        };
-        Ok(Self {
-            client,
-            api_root: api_root.to_string(),
-            revolt_config,
-            auth_type,
-        })
+        Self::builder().api_root(api_root).build(auth).await
     }
 
-    fn client_user_session_auth_type(&self) -> &reqwest::Client {
-        match self.auth_type {
-            AuthType::Bot => panic!("Cannot use route when using a bot auth"),
-            AuthType::UserSession => &self.client,
-        }
+    /// Creates a client from an already known configuration of the revolt instance,
+    /// without making any requests.
+    ///
+    /// The configuration can be obtained from [`Self::get_revolt_configuration`]
+    /// on another client, and serialized to be reused later.
+    pub fn from_parts<'auth>(
+        auth: impl Into<HttpAuthentication<'auth>>,
+        api_root: &str,
+        revolt_config: RevoltConfiguration,
+    ) -> Result<Self> {
+        Self::builder()
+            .api_root(api_root)
+            .build_with_configuration(auth, revolt_config)
     }
 
-    /// Gets the websocket url
-    pub fn get_ws_url(&self) -> &str {
-        &self.revolt_config.ws
+    /// Creates a builder, to configure the http client before creating it.
+    pub fn builder() -> HttpBuilder {
+        HttpBuilder::new()
     }
 
-    pub async fn get_self_id(&self) -> Result<UserId> {
-        Ok(self.fetch_account().await?.id)
+    /// Returns a client sharing the same connection pool and rate limits,
+    /// which retries requests according to `retry_policy`.
+    ///
+    /// Use this to override the retry policy for a single call site:
+    /// ```no_run
+    /// # use robespierre_http::{Http, RetryPolicy};
+    /// # async fn f(http: &Http, server: robespierre_models::id::ServerId) -> robespierre_http::Result {
+    /// let server = http
+    ///     .with_retry_policy(RetryPolicy::default().max_attempts(5))
+    ///     .fetch_server(server)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_retry_policy(&self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            client: self.client.with_retry_policy(retry_policy),
+            ..self.clone()
+        }
     }
 
-    pub fn get_revolt_configuration(&self) -> &RevoltConfiguration {
-        &self.revolt_config
+    /// Gets the kind of authentication this client uses.
+    pub fn auth_type(&self) -> AuthType {
+        self.auth_type
     }
 
-    /// Uploads a file to autumn, returning the [`AttachmentId`]
-    pub async fn upload_autumn(
-        &self,
-        tag: AttachmentTag,
-        name: String,
-        bytes: Vec<u8>,
-    ) -> Result<AttachmentId> {
-        #[derive(serde::Deserialize)]
-        struct AutumnUploadResponse {
-            id: AttachmentId,
+    /// Gets a [`UserHttp`] sharing this client's connection pool and rate limits,
+    /// to use the routes only available to users.
+    ///
+    /// Returns `None` if this client is authenticated as a bot.
+    pub fn to_user(&self) -> Option<UserHttp> {
+        match self.auth_type {
+            AuthType::Bot => None,
+            AuthType::UserSession => Some(UserHttp(self.clone())),
         }
+    }
 
-        let part = Part::bytes(bytes).file_name(name.clone());
-        let form = Form::new().part(name, part);
-        let req = self
-            .client
-            .post(autumn_tag_upload!(self, tag))
-            .multipart(form);
-        let resp = req
-            .send()
-            .await?
-            .error_for_status()?
-            .json::<AutumnUploadResponse>()
-            .await?;
-        Ok(resp.id)
+    pub async fn get_self_id(&self) -> Result<UserId> {
+        Ok(self.fetch_self().await?.id)
     }
 }
 
@@ -237,6 +340,6 @@ impl HasHttp for Http {
 
 impl<T: Deref<Target = Http> + Send + Sync> HasHttp for T {
     fn get_http(&self) -> &Http {
-        &**self
+        self
     }
 }
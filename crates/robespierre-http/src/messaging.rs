@@ -1,6 +1,6 @@
 use robespierre_models::{
     autumn::AttachmentId,
-    channels::{Message, MessageFilter, ReplyData},
+    channels::{Message, MessageFilter, MessageSearchOptions, ReplyData},
     id::{ChannelId, MessageId},
     servers::Member,
     users::User,
@@ -18,18 +18,22 @@ impl Http {
         attachments: Vec<AttachmentId>,
         replies: Vec<ReplyData>,
     ) -> Result<Message> {
+        let nonce = nonce.as_ref();
+
         Ok(self
             .client
             .post(ep!(self, "/channels/{}/messages" channel_id))
+            // the server rejects messages with a nonce it has already seen,
+            // so a retry can't send the message twice
+            .idempotent(!nonce.is_empty())
             .json(&SendMessageRequest {
                 content: content.as_ref(),
-                nonce: nonce.as_ref(),
+                nonce,
                 attachments,
                 replies,
             })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -43,22 +47,13 @@ impl Http {
         let v = self
             .client
             .get(ep!(self, "/channels/{}/messages" channel))
-            .json(&filter)
+            .query(&filter)
             .send()
             .await?
-            .error_for_status()?
             .json::<serde_json::Value>()
             .await?;
 
-        if matches!(&filter.include_users, Some(true)) {
-            Ok(serde_json::from_value(v)?)
-        } else {
-            Ok(FetchMessagesResult {
-                messages: serde_json::from_value(v)?,
-                users: vec![],
-                members: vec![],
-            })
-        }
+        FetchMessagesResult::from_value(v, filter.include_users)
     }
 
     /// Fetches a single message
@@ -68,7 +63,6 @@ impl Http {
             .get(ep!(self, "/channels/{}/messages/{}" channel, message))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -88,8 +82,7 @@ impl Http {
             .patch(ep!(self, "/channels/{}/messages/{}" channel, message))
             .json(&MessagePatch { content })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
         Ok(())
     }
 
@@ -98,8 +91,7 @@ impl Http {
         self.client
             .delete(ep!(self, "/channels/{}/messages/{}" channel, message))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
         Ok(())
     }
 
@@ -119,19 +111,48 @@ impl Http {
             .json(&PollMessageChanges { ids })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
-    // TODO: search for messages
+    /// Searches for messages matching `query`
+    pub async fn search_messages(
+        &self,
+        channel: ChannelId,
+        query: &str,
+        options: MessageSearchOptions,
+    ) -> Result<FetchMessagesResult> {
+        #[derive(serde::Serialize)]
+        struct SearchMessagesRequest<'a> {
+            query: &'a str,
+            #[serde(flatten)]
+            options: &'a MessageSearchOptions,
+        }
+
+        let v = self
+            .client
+            .post(ep!(self, "/channels/{}/search" channel))
+            // searching has no side effects
+            .idempotent(true)
+            .json(&SearchMessagesRequest {
+                query,
+                options: &options,
+            })
+            .send()
+            .await?
+            .json::<serde_json::Value>()
+            .await?;
+
+        FetchMessagesResult::from_value(v, options.include_users)
+    }
+}
 
+impl UserHttp {
     pub async fn acknowledge_message(&self, channel: ChannelId, message: MessageId) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .put(ep!(self, "/channels/{}/ack/{}" channel, message))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -162,3 +183,105 @@ pub struct FetchMessagesResult {
     #[serde(default)]
     pub members: Vec<Member>,
 }
+
+impl FetchMessagesResult {
+    /// The api returns only the list of messages if the users weren't requested.
+    fn from_value(v: serde_json::Value, include_users: Option<bool>) -> Result<Self> {
+        if matches!(include_users, Some(true)) {
+            Ok(serde_json::from_value(v)?)
+        } else {
+            Ok(Self {
+                messages: serde_json::from_value(v)?,
+                users: vec![],
+                members: vec![],
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::Method;
+    use robespierre_models::channels::MessageSearchSortDirection;
+
+    use super::*;
+    use crate::{
+        test_utils::{mock_http, MockTransport},
+        Http, HttpAuthentication,
+    };
+
+    const CHANNEL: &str = "01FE9XVK2GXQXHZ5BCZBD6PA8P";
+    const MESSAGE: &str = r#"{"_id":"01FE9XXP4NWJ6S2D4N7WX6W3AJ","channel":"01FE9XVK2GXQXHZ5BCZBD6PA8P","author":"01FE9XNFEDH6NSPKRQY5SVXYRP","content":"hello"}"#;
+
+    #[tokio::test(start_paused = true)]
+    async fn searches_are_retried() {
+        let transport = Arc::new(MockTransport::default());
+        transport.respond(503, "");
+        transport.respond(200, format!("[{}]", MESSAGE));
+
+        // the default retry policy retries 503s, but only for idempotent requests
+        let http = Http::builder()
+            .api_root("https://revolt.test")
+            .transport(Arc::clone(&transport))
+            .build_lazy(HttpAuthentication::BotToken { token: "token" })
+            .unwrap();
+        let options = MessageSearchOptions {
+            limit: Some(10),
+            ..Default::default()
+        };
+        let result = http
+            .search_messages(CHANNEL.parse().unwrap(), "hello", options)
+            .await
+            .unwrap();
+
+        assert_eq!(result.messages.len(), 1);
+        assert!(result.users.is_empty());
+
+        let requests = transport.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        for (method, url, _) in requests.iter() {
+            assert_eq!(method, Method::POST);
+            assert_eq!(
+                url,
+                "https://revolt.test/channels/01FE9XVK2GXQXHZ5BCZBD6PA8P/search"
+            );
+        }
+        assert_eq!(
+            transport.json_body(0),
+            serde_json::json!({ "query": "hello", "limit": 10, "sort": "Relevance" })
+        );
+    }
+
+    #[tokio::test]
+    async fn searches_include_users_when_asked() {
+        let transport = Arc::new(MockTransport::default());
+        transport.respond(
+            200,
+            format!(
+                r#"{{"messages":[{}],"users":[{{"_id":"01FE9XNFEDH6NSPKRQY5SVXYRP","username":"author"}}]}}"#,
+                MESSAGE
+            ),
+        );
+
+        let http = mock_http(&transport);
+        let options = MessageSearchOptions {
+            sort: MessageSearchSortDirection::Latest,
+            include_users: Some(true),
+            ..Default::default()
+        };
+        let result = http
+            .search_messages(CHANNEL.parse().unwrap(), "hello", options)
+            .await
+            .unwrap();
+
+        assert_eq!(result.messages.len(), 1);
+        assert_eq!(result.users.len(), 1);
+        assert!(result.members.is_empty());
+        assert_eq!(
+            transport.json_body(0),
+            serde_json::json!({ "query": "hello", "sort": "Latest", "include_users": true })
+        );
+    }
+}
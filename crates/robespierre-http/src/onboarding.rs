@@ -1,14 +1,13 @@
 use super::impl_prelude::*;
 
-impl Http {
+impl UserHttp {
     // onboarding
     pub async fn get_onboarding(&self) -> Result<OnboardingStatus> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/onboard/hello"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -19,12 +18,11 @@ impl Http {
             username: &'a str,
         }
 
-        self.client_user_session_auth_type()
+        self.client
             .post(ep!(self, "/onboard/complete"))
             .json(&CompleteOnboardingRequest { username })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -0,0 +1,195 @@
+//! Per-route rate limit buckets.
+//!
+//! Revolt reports the state of the bucket a route belongs to through the
+//! `X-RateLimit-*` headers on every response. The [`Ratelimiter`] keeps track of
+//! them, and holds back requests that would go over the limit until the bucket
+//! resets, instead of letting the server reject them with a `429 Too Many Requests`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex},
+    time::Duration,
+};
+
+use reqwest::{header::HeaderMap, Method, StatusCode};
+use tokio::{sync::Mutex, time::Instant};
+
+/// How many times a request is retried after getting a 429 before
+/// giving up and returning the error.
+const MAX_RATELIMITED_RETRIES: usize = 5;
+
+/// The key a bucket is stored under: the method and the route template,
+/// like `/channels/{}/messages`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RouteKey {
+    pub(crate) method: Method,
+    pub(crate) template: &'static str,
+}
+
+#[derive(Debug, Default)]
+struct BucketState {
+    limit: Option<u32>,
+    remaining: Option<u32>,
+    reset_at: Option<Instant>,
+}
+
+/// A single rate limit bucket.
+///
+/// Requests waiting on the bucket are queued in the order they arrived.
+#[derive(Debug, Default)]
+pub(crate) struct Bucket {
+    state: Mutex<BucketState>,
+}
+
+impl Bucket {
+    /// Waits until the bucket has room for another request, and takes it.
+    pub(crate) async fn acquire(&self) {
+        let mut state = self.state.lock().await;
+
+        if let Some(reset_at) = state.reset_at {
+            if state.remaining == Some(0) && Instant::now() < reset_at {
+                tracing::debug!("Bucket exhausted, waiting {:?}", reset_at - Instant::now());
+                // keep holding the lock, so that other requests queue up behind this one
+                tokio::time::sleep_until(reset_at).await;
+            }
+
+            if Instant::now() >= reset_at {
+                state.remaining = state.limit;
+                state.reset_at = None;
+            }
+        }
+
+        if let Some(remaining) = state.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+    }
+
+    /// Updates the bucket from the headers of a response.
+    pub(crate) async fn update(&self, headers: &HeaderMap) {
+        let limit = header::<u32>(headers, "x-ratelimit-limit");
+        let remaining = header::<u32>(headers, "x-ratelimit-remaining");
+        let reset_after = header::<u64>(headers, "x-ratelimit-reset-after");
+
+        let mut state = self.state.lock().await;
+        if let Some(limit) = limit {
+            state.limit = Some(limit);
+        }
+        if let Some(remaining) = remaining {
+            state.remaining = Some(remaining);
+        }
+        if let Some(reset_after) = reset_after {
+            state.reset_at = Some(Instant::now() + Duration::from_millis(reset_after));
+        }
+    }
+
+    /// Marks the bucket as exhausted for `retry_after`, after the server
+    /// rejected a request with a 429.
+    pub(crate) async fn exhaust(&self, retry_after: Duration) {
+        let mut state = self.state.lock().await;
+        state.remaining = Some(0);
+        state.reset_at = Some(Instant::now() + retry_after);
+    }
+}
+
+/// Keeps track of the rate limit buckets of the routes used by an [`crate::Http`].
+#[derive(Debug, Default)]
+pub struct Ratelimiter {
+    buckets: StdMutex<HashMap<RouteKey, Arc<Bucket>>>,
+}
+
+impl Ratelimiter {
+    /// Creates a new ratelimiter, with no known buckets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn bucket(&self, key: &RouteKey) -> Arc<Bucket> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        match buckets.get(key) {
+            Some(bucket) => Arc::clone(bucket),
+            None => {
+                let bucket = Arc::new(Bucket::default());
+                buckets.insert(key.clone(), Arc::clone(&bucket));
+                bucket
+            }
+        }
+    }
+
+    pub(crate) fn max_retries(&self) -> usize {
+        MAX_RATELIMITED_RETRIES
+    }
+}
+
+/// Gets how long to wait before retrying a request rejected with
+/// `status`, if it was rejected because of a rate limit.
+///
+/// Prefers the standard `Retry-After` header (seconds), and falls back to
+/// Revolt's `X-RateLimit-Reset-After` (milliseconds).
+pub(crate) fn retry_after(status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    header::<u64>(headers, "retry-after")
+        .map(Duration::from_secs)
+        .or_else(|| header::<u64>(headers, "x-ratelimit-reset-after").map(Duration::from_millis))
+}
+
+fn header<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(values: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in values {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn retry_after_prefers_standard_header() {
+        let h = headers(&[("retry-after", "2"), ("x-ratelimit-reset-after", "500")]);
+        assert_eq!(
+            retry_after(StatusCode::TOO_MANY_REQUESTS, &h),
+            Some(Duration::from_secs(2))
+        );
+
+        let h = headers(&[("x-ratelimit-reset-after", "500")]);
+        assert_eq!(
+            retry_after(StatusCode::TOO_MANY_REQUESTS, &h),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(retry_after(StatusCode::OK, &h), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exhausted_bucket_waits_for_reset() {
+        let bucket = Bucket::default();
+        bucket
+            .update(&headers(&[
+                ("x-ratelimit-limit", "10"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset-after", "3000"),
+            ]))
+            .await;
+
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert!(Instant::now() - start >= Duration::from_millis(3000));
+
+        // the bucket was refilled, and one request was taken out of it
+        assert_eq!(bucket.state.lock().await.remaining, Some(9));
+
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert_eq!(Instant::now(), start);
+    }
+}
@@ -5,15 +5,14 @@ use robespierre_models::{
 
 use super::impl_prelude::*;
 
-impl Http {
+impl UserHttp {
     /// Fetches relationships of the current user
     pub async fn fetch_relationships(&self) -> Result<Vec<Relationship>> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/users/relationships"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -21,11 +20,10 @@ impl Http {
     /// Fetches your relationship with the given user
     pub async fn fetch_relationship(&self, user_id: UserId) -> Result<SingleRelationshipResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .get(ep!(self, "/users/{}/relationship" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -33,11 +31,10 @@ impl Http {
     /// Sends or accepts a friend request to / from the user with given username
     pub async fn send_friend_request(&self, username: &str) -> Result<SingleRelationshipResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .put(ep!(self, "/users/{}/friend" username))
             .send()
             .await?
-            .error_for_status()?
             .json::<SingleRelationshipResponse>()
             .await?)
     }
@@ -45,11 +42,10 @@ impl Http {
     /// Denies a friend request
     pub async fn deny_friend_request(&self, username: &str) -> Result<SingleRelationshipResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .delete(ep!(self, "/users/{}/friend" username))
             .send()
             .await?
-            .error_for_status()?
             .json::<SingleRelationshipResponse>()
             .await?)
     }
@@ -61,11 +57,10 @@ impl Http {
     /// Blocks an user
     pub async fn block_user(&self, user_id: UserId) -> Result<SingleRelationshipResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .put(ep!(self, "/users/{}/block" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json::<SingleRelationshipResponse>()
             .await?)
     }
@@ -73,11 +68,10 @@ impl Http {
     /// Unblocks an user
     pub async fn unblock(&self, user_id: UserId) -> Result<SingleRelationshipResponse> {
         Ok(self
-            .client_user_session_auth_type()
+            .client
             .delete(ep!(self, "/users/{}/block" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json::<SingleRelationshipResponse>()
             .await?)
     }
@@ -0,0 +1,309 @@
+use std::{sync::Arc, time::Instant};
+
+use reqwest::{
+    header::{HeaderName, HeaderValue, ACCEPT},
+    multipart::Form,
+    Method, Request, Response,
+};
+use serde::Serialize;
+
+use crate::{
+    interceptor::{Interceptor, RequestInfo, ResponseInfo},
+    ratelimit::{self, Ratelimiter, RouteKey},
+    retry::{self, RetryPolicy},
+    transport::Transport,
+    ApiError, HttpError, Result,
+};
+
+/// A route on the api, as the template it was made from (`/channels/{}/messages`)
+/// and the url it expands to.
+#[derive(Debug, Clone)]
+pub(crate) struct Route {
+    pub(crate) template: &'static str,
+    pub(crate) url: String,
+}
+
+impl Route {
+    pub(crate) fn new(template: &'static str, url: String) -> Self {
+        Self { template, url }
+    }
+}
+
+/// Wraps the [`Transport`], making all the requests go through the [`Ratelimiter`],
+/// and retrying them according to the [`RetryPolicy`].
+///
+/// Requests are still built with a [`reqwest::Client`], whatever the transport is.
+#[derive(Clone)]
+pub(crate) struct Client {
+    client: reqwest::Client,
+    transport: Arc<dyn Transport>,
+    interceptors: Arc<[Box<dyn Interceptor>]>,
+    auth: Option<(HeaderName, HeaderValue)>,
+    ratelimiter: Arc<Ratelimiter>,
+    retry_policy: Arc<RetryPolicy>,
+}
+
+impl Client {
+    pub(crate) fn new(
+        client: reqwest::Client,
+        transport: Arc<dyn Transport>,
+        interceptors: Vec<Box<dyn Interceptor>>,
+        auth: Option<(HeaderName, HeaderValue)>,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            client,
+            transport,
+            interceptors: interceptors.into(),
+            auth,
+            ratelimiter: Arc::new(Ratelimiter::new()),
+            retry_policy: Arc::new(retry_policy),
+        }
+    }
+
+    /// A client sharing the same connection pool and rate limits,
+    /// but with a different retry policy.
+    pub(crate) fn with_retry_policy(&self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy: Arc::new(retry_policy),
+            ..self.clone()
+        }
+    }
+
+    /// A client without any authentication, for the routes that don't require it.
+    pub(crate) fn unauthenticated() -> Self {
+        let client = reqwest::Client::new();
+        Self::new(
+            client.clone(),
+            Arc::new(client),
+            vec![],
+            None,
+            RetryPolicy::default(),
+        )
+    }
+
+    pub(crate) fn request(&self, method: Method, route: Route) -> RequestBuilder {
+        let mut inner = self
+            .client
+            .request(method.clone(), route.url)
+            .header(ACCEPT, "*/*");
+        if let Some((name, value)) = &self.auth {
+            inner = inner.header(name, value);
+        }
+
+        RequestBuilder {
+            client: self.clone(),
+            key: RouteKey {
+                method,
+                template: route.template,
+            },
+            inner,
+            idempotent: None,
+        }
+    }
+
+    pub(crate) fn get(&self, route: Route) -> RequestBuilder {
+        self.request(Method::GET, route)
+    }
+
+    pub(crate) fn post(&self, route: Route) -> RequestBuilder {
+        self.request(Method::POST, route)
+    }
+
+    pub(crate) fn put(&self, route: Route) -> RequestBuilder {
+        self.request(Method::PUT, route)
+    }
+
+    pub(crate) fn patch(&self, route: Route) -> RequestBuilder {
+        self.request(Method::PATCH, route)
+    }
+
+    pub(crate) fn delete(&self, route: Route) -> RequestBuilder {
+        self.request(Method::DELETE, route)
+    }
+}
+
+impl Client {
+    /// Hands the request to the transport, calling the interceptors around it.
+    async fn execute(
+        &self,
+        mut request: Request,
+        key: &RouteKey,
+        attempt: u32,
+    ) -> Result<Response> {
+        let info = RequestInfo {
+            method: &key.method,
+            route: key.template,
+            attempt,
+        };
+        for interceptor in self.interceptors.iter() {
+            interceptor.before_send(&mut request, &info);
+        }
+
+        let start = Instant::now();
+        let mut result = self.transport.execute(request).await;
+
+        let info = ResponseInfo {
+            method: &key.method,
+            route: key.template,
+            attempt,
+            elapsed: start.elapsed(),
+        };
+        for interceptor in self.interceptors.iter() {
+            interceptor.after_receive(&mut result, &info);
+        }
+
+        result
+    }
+}
+
+/// A request to a [`Route`], that hasn't been sent yet.
+pub(crate) struct RequestBuilder {
+    client: Client,
+    key: RouteKey,
+    inner: reqwest::RequestBuilder,
+    idempotent: Option<bool>,
+}
+
+impl RequestBuilder {
+    pub(crate) fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        Self {
+            inner: self.inner.json(json),
+            ..self
+        }
+    }
+
+    pub(crate) fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        Self {
+            inner: self.inner.query(query),
+            ..self
+        }
+    }
+
+    pub(crate) fn multipart(self, form: Form) -> Self {
+        Self {
+            inner: self.inner.multipart(form),
+            ..self
+        }
+    }
+
+    /// Overrides whether the request can be safely retried, which
+    /// by default depends only on the method.
+    pub(crate) fn idempotent(self, idempotent: bool) -> Self {
+        Self {
+            idempotent: Some(idempotent),
+            ..self
+        }
+    }
+
+    /// Sends the request, waiting for the route's bucket first, and
+    /// waiting out and retrying on `429 Too Many Requests`.
+    ///
+    /// Idempotent requests that fail with a transient error are retried
+    /// according to the client's [`RetryPolicy`].
+    ///
+    /// Returns [`HttpError::Api`] if the response has an error status code.
+    pub(crate) async fn send(self) -> Result<Response> {
+        let Self {
+            client,
+            key,
+            inner,
+            idempotent,
+        } = self;
+
+        let idempotent = idempotent.unwrap_or_else(|| retry::is_idempotent(&key.method));
+        let policy = &client.retry_policy;
+        let bucket = client.ratelimiter.bucket(&key);
+        let mut request = inner.build()?;
+        let mut ratelimited_retries = 0;
+        let mut attempt = 1;
+
+        loop {
+            // bodies that are streams (multipart) can't be cloned, and so can't be retried
+            let retry_request = request.try_clone();
+
+            bucket.acquire().await;
+            // every send counts for the interceptors, but only failures count for the retry policy
+            let sends = attempt + ratelimited_retries as u32;
+            let response = match client.execute(request, &key, sends).await {
+                Ok(response) => response,
+                Err(e) => match retry_request {
+                    Some(retry_request)
+                        if idempotent
+                            && policy.can_retry(attempt)
+                            && policy.is_retryable_error(&e) =>
+                    {
+                        let delay = policy.delay(attempt);
+                        tracing::debug!(
+                            "{} {} failed ({}), retrying after {:?}",
+                            key.method,
+                            key.template,
+                            e,
+                            delay
+                        );
+                        tokio::time::sleep(delay).await;
+                        request = retry_request;
+                        attempt += 1;
+                        continue;
+                    }
+                    _ => return Err(e),
+                },
+            };
+            bucket.update(response.headers()).await;
+
+            let retry_request = match retry_request {
+                Some(retry_request) => retry_request,
+                None => return check_status(response).await,
+            };
+
+            if let Some(retry_after) = ratelimit::retry_after(response.status(), response.headers())
+            {
+                if ratelimited_retries >= client.ratelimiter.max_retries() {
+                    return check_status(response).await;
+                }
+
+                tracing::debug!(
+                    "Ratelimited on {} {}, retrying after {:?}",
+                    key.method,
+                    key.template,
+                    retry_after
+                );
+                bucket.exhaust(retry_after).await;
+                request = retry_request;
+                ratelimited_retries += 1;
+            } else if idempotent
+                && policy.can_retry(attempt)
+                && policy.is_retryable_status(response.status())
+            {
+                let delay = policy.delay(attempt);
+                tracing::debug!(
+                    "{} {} failed ({}), retrying after {:?}",
+                    key.method,
+                    key.template,
+                    response.status(),
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                request = retry_request;
+                attempt += 1;
+            } else {
+                return check_status(response).await;
+            }
+        }
+    }
+}
+
+async fn check_status(response: Response) -> Result<Response> {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return Ok(response);
+    }
+
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    let error = ApiError::from_response(status, &headers, &body);
+
+    tracing::debug!("Got error {:?} ({})", error, status);
+
+    Err(HttpError::Api { status, error })
+}
@@ -0,0 +1,162 @@
+//! Retrying requests that failed because of transient errors.
+
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::{Method, StatusCode};
+
+use crate::HttpError;
+
+/// Describes when and how a failed request is retried.
+///
+/// Only requests that are idempotent are retried: `GET`, `PUT`, `DELETE` requests,
+/// and requests that carry a nonce, like [`crate::Http::send_message`], as the server
+/// will reject the duplicates.
+///
+/// Rate limits are handled separately, by the [`crate::ratelimit::Ratelimiter`], and
+/// don't count as attempts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    statuses: Vec<StatusCode>,
+    connection_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Tries a request at most 3 times, retrying on connection errors,
+    /// timeouts and on `500`, `502`, `503` and `504` responses.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            statuses: vec![
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            connection_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// The maximum number of times a request is tried, including the first try.
+    pub fn max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// The delay before the first retry. Doubles with every following retry.
+    pub fn base_delay(self, base_delay: Duration) -> Self {
+        Self { base_delay, ..self }
+    }
+
+    /// The maximum delay between two tries.
+    pub fn max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    /// Whether to wait a random duration between 0 and the computed delay,
+    /// instead of the delay itself.
+    pub fn jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// The status codes of the responses that should be retried.
+    pub fn retry_statuses(self, statuses: Vec<StatusCode>) -> Self {
+        Self { statuses, ..self }
+    }
+
+    /// Whether to retry on connection errors and timeouts.
+    pub fn retry_connection_errors(self, connection_errors: bool) -> Self {
+        Self {
+            connection_errors,
+            ..self
+        }
+    }
+
+    pub(crate) fn can_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    pub(crate) fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.statuses.contains(&status)
+    }
+
+    pub(crate) fn is_retryable_error(&self, error: &HttpError) -> bool {
+        match error {
+            HttpError::Reqwest(e) => {
+                self.connection_errors && (e.is_connect() || e.is_timeout() || e.is_request())
+            }
+            _ => false,
+        }
+    }
+
+    /// The delay before retrying, after `attempt` tries failed.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+
+        if self.jitter {
+            delay.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+/// Whether requests with the given method can be safely repeated.
+pub(crate) fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff() {
+        let policy = RetryPolicy::default()
+            .jitter(false)
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(500));
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(4), Duration::from_millis(500));
+        assert_eq!(policy.delay(100), Duration::from_millis(500));
+
+        let policy = policy.jitter(true);
+        for attempt in 1..10 {
+            assert!(policy.delay(attempt) <= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn none_never_retries() {
+        let policy = RetryPolicy::none();
+        assert!(!policy.can_retry(1));
+        assert!(RetryPolicy::default().can_retry(2));
+        assert!(!RetryPolicy::default().can_retry(3));
+    }
+}
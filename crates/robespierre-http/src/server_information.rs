@@ -1,7 +1,7 @@
 use robespierre_models::{
     channels::{ChannelInviteCode, ServerChannelType},
     id::{ChannelId, ServerId, UserId},
-    servers::{PartialServer, Server, ServerField},
+    servers::{Server, ServerEditPatch},
 };
 
 use super::impl_prelude::*;
@@ -14,31 +14,17 @@ impl Http {
             .get(ep!(self, "/servers/{}" server))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
-    /// Edits a server
-    pub async fn edit_server(
-        &self,
-        server_id: ServerId,
-        server: PartialServer,
-        remove: ServerField,
-    ) -> Result {
-        #[derive(serde::Serialize)]
-        struct ServerPatchRequest {
-            #[serde(flatten)]
-            server: PartialServer,
-            remove: ServerField,
-        }
-
+    /// Edits a server, like its name, categories and system message channels
+    pub async fn edit_server(&self, server_id: ServerId, patch: ServerEditPatch) -> Result {
         self.client
             .patch(ep!(self, "/servers/{}" server_id))
-            .json(&ServerPatchRequest { server, remove })
+            .json(&patch)
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -48,45 +34,11 @@ impl Http {
         self.client
             .delete(ep!(self, "/servers/{}" server_id))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
 
-    /// Creates a server
-    pub async fn create_server(
-        &self,
-        name: String,
-        description: Option<String>,
-        nsfw: Option<bool>,
-        nonce: String,
-    ) -> Result<Server> {
-        #[derive(serde::Serialize)]
-        struct CreateServerRequest {
-            name: String,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            description: Option<String>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            nsfw: Option<bool>,
-            nonce: String,
-        }
-        Ok(self
-            .client_user_session_auth_type()
-            .post(ep!(self, "/servers/create"))
-            .json(&CreateServerRequest {
-                name,
-                description,
-                nsfw,
-                nonce,
-            })
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
-    }
-
     /// Creates a channel
     pub async fn create_channel(
         &self,
@@ -108,6 +60,7 @@ impl Http {
 
         self.client
             .post(ep!(self, "/servers/{}/channels" server))
+            .idempotent(!nonce.is_empty())
             .json(&CreateServerChannelRequest {
                 kind,
                 name,
@@ -115,8 +68,7 @@ impl Http {
                 nonce,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -128,18 +80,51 @@ impl Http {
             .get(ep!(self, "/servers/{}/invites" server))
             .send()
             .await?
-            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+impl UserHttp {
+    /// Creates a server
+    pub async fn create_server(
+        &self,
+        name: String,
+        description: Option<String>,
+        nsfw: Option<bool>,
+        nonce: String,
+    ) -> Result<Server> {
+        #[derive(serde::Serialize)]
+        struct CreateServerRequest {
+            name: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            description: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            nsfw: Option<bool>,
+            nonce: String,
+        }
+        Ok(self
+            .client
+            .post(ep!(self, "/servers/create"))
+            .idempotent(!nonce.is_empty())
+            .json(&CreateServerRequest {
+                name,
+                description,
+                nsfw,
+                nonce,
+            })
+            .send()
+            .await?
             .json()
             .await?)
     }
 
     /// Marks server as read
     pub async fn mark_server_as_read(&self, server: ServerId) -> Result {
-        self.client_user_session_auth_type()
+        self.client
             .put(ep!(self, "/servers/{}/ack" server))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
         Ok(())
     }
 }
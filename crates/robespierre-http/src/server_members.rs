@@ -15,7 +15,6 @@ impl Http {
             .get(ep!(self, "/servers/{}/members/{}" server_id, user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -38,8 +37,7 @@ impl Http {
             .patch(ep!(self, "/servers/{}/members/{}" server_id, user_id))
             .json(&PatchMemberRequest { member, remove })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
         Ok(())
     }
 
@@ -48,8 +46,7 @@ impl Http {
         self.client
             .delete(ep!(self, "/servers/{}/members/{}" server_id, user_id))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -61,7 +58,6 @@ impl Http {
             .get(ep!(self, "/servers/{}/members" server_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -82,8 +78,7 @@ impl Http {
             .put(ep!(self, "/servers/{}/bans/{}" server_id, user_id))
             .json(&BanRequest { reason })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -93,8 +88,7 @@ impl Http {
         self.client
             .delete(ep!(self, "/servers/{}/bans/{}" server_id, user_id))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -106,7 +100,6 @@ impl Http {
             .get(ep!(self, "/servers/{}/bans" server_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
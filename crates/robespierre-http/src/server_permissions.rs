@@ -1,7 +1,7 @@
 use robespierre_models::{
     channels::ChannelPermissions,
     id::{RoleId, ServerId},
-    servers::{PermissionTuple, ServerPermissions},
+    servers::{PermissionTuple, RoleEditPatch, ServerPermissions},
 };
 
 use super::impl_prelude::*;
@@ -22,8 +22,7 @@ impl Http {
                 channel: cp,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -42,8 +41,7 @@ impl Http {
                 channel: cp,
             })
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -60,19 +58,26 @@ impl Http {
             .json(&CreateRoleRequest { name })
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 
-    // TODO: edit role
+    /// Edits a role
+    pub async fn edit_role(&self, server: ServerId, role: RoleId, patch: RoleEditPatch) -> Result {
+        self.client
+            .patch(ep!(self, "/servers/{}/roles/{}" server, role))
+            .json(&patch)
+            .send()
+            .await?;
+
+        Ok(())
+    }
 
     pub async fn delete_role(&self, server: ServerId, role: RoleId) -> Result {
         self.client
             .delete(ep!(self, "/servers/{}/roles/{}" server, role))
             .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -1 +1,85 @@
-// TODO sync
+use std::collections::HashMap;
+
+use robespierre_models::sync::{ChannelUnread, UserSettings};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::impl_prelude::*;
+
+impl UserHttp {
+    /// Fetches the settings with the given keys
+    pub async fn fetch_settings(&self, keys: &[&str]) -> Result<UserSettings> {
+        #[derive(serde::Serialize)]
+        struct FetchSettingsRequest<'a> {
+            keys: &'a [&'a str],
+        }
+
+        Ok(self
+            .client
+            .post(ep!(self, "/sync/settings/fetch"))
+            // fetching has no side effects
+            .idempotent(true)
+            .json(&FetchSettingsRequest { keys })
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// Sets settings, mapping keys to their (usually json-encoded) values.
+    ///
+    /// `timestamp` is when the settings were changed, in milliseconds since
+    /// the unix epoch; the server uses the current time if it is `None`.
+    pub async fn set_settings(
+        &self,
+        settings: &HashMap<String, String>,
+        timestamp: Option<u64>,
+    ) -> Result {
+        #[derive(serde::Serialize)]
+        struct SetSettingsQuery {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            timestamp: Option<u64>,
+        }
+
+        self.client
+            .post(ep!(self, "/sync/settings/set"))
+            // setting the same values again has no effect
+            .idempotent(true)
+            .query(&SetSettingsQuery { timestamp })
+            .json(settings)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetches a single setting, decoding its value from json.
+    ///
+    /// Returns `None` if the setting was never set.
+    pub async fn fetch_setting<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let mut settings = self.fetch_settings(&[key]).await?;
+
+        match settings.0.remove(key) {
+            Some((_timestamp, value)) => Ok(Some(serde_json::from_str(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets a single setting, encoding its value as json.
+    pub async fn set_setting<T: Serialize + ?Sized>(&self, key: &str, value: &T) -> Result {
+        let mut settings = HashMap::new();
+        settings.insert(key.to_string(), serde_json::to_string(value)?);
+
+        self.set_settings(&settings, None).await
+    }
+
+    /// Fetches the unread state of all the channels
+    pub async fn fetch_unreads(&self) -> Result<Vec<ChannelUnread>> {
+        Ok(self
+            .client
+            .get(ep!(self, "/sync/unreads"))
+            .send()
+            .await?
+            .json()
+            .await?)
+    }
+}
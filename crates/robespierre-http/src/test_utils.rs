@@ -0,0 +1,61 @@
+//! A mock [`Transport`] that records the requests going through it,
+//! to test the requests made by the routes.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use reqwest::{Method, Request, Response};
+
+use crate::{transport::Transport, Http, HttpAuthentication, Result, RetryPolicy};
+
+/// Records the requests, by method, url and body, and answers
+/// them with the queued responses, in order.
+#[derive(Default)]
+pub(crate) struct MockTransport {
+    pub(crate) requests: Mutex<Vec<(Method, String, Option<String>)>>,
+    pub(crate) responses: Mutex<Vec<(u16, String)>>,
+}
+
+impl MockTransport {
+    pub(crate) fn respond(&self, status: u16, body: impl Into<String>) {
+        self.responses.lock().unwrap().push((status, body.into()));
+    }
+
+    /// The body of the `i`th request, as json.
+    pub(crate) fn json_body(&self, i: usize) -> serde_json::Value {
+        let requests = self.requests.lock().unwrap();
+        serde_json::from_str(requests[i].2.as_ref().unwrap()).unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| String::from_utf8(body.to_vec()).unwrap());
+        self.requests.lock().unwrap().push((
+            request.method().clone(),
+            request.url().to_string(),
+            body,
+        ));
+
+        let (status, body) = self.responses.lock().unwrap().remove(0);
+        Ok(http::Response::builder()
+            .status(status)
+            .body(body)
+            .unwrap()
+            .into())
+    }
+}
+
+/// A client that doesn't retry, going through `transport`.
+pub(crate) fn mock_http(transport: &Arc<MockTransport>) -> Http {
+    Http::builder()
+        .api_root("https://revolt.test")
+        .transport(Arc::clone(transport))
+        .retry_policy(RetryPolicy::none())
+        .build_lazy(HttpAuthentication::BotToken { token: "token" })
+        .unwrap()
+}
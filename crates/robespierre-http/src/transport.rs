@@ -0,0 +1,113 @@
+//! The layer that actually sends the requests.
+//!
+//! By default requests are sent over the network with a [`reqwest::Client`], but
+//! any [`Transport`] can be plugged into an [`crate::Http`] with
+//! [`crate::HttpBuilder::transport`], for example to drive the whole api from
+//! an in-memory implementation in tests.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::{Request, Response};
+
+use crate::Result;
+
+/// Sends requests built by an [`crate::Http`], and returns their responses.
+///
+/// Requests come in fully built, with the authentication header set; rate limiting,
+/// retries and error status handling happen on top of the transport.
+///
+/// A [`Response`] can be built from an `http::Response`, with [`From`]:
+/// ```
+/// # use robespierre_http::{reqwest::{Request, Response}, transport::Transport};
+/// struct AlwaysNotFound;
+///
+/// #[async_trait::async_trait]
+/// impl Transport for AlwaysNotFound {
+///     async fn execute(&self, _request: Request) -> robespierre_http::Result<Response> {
+///         let response = http::Response::builder()
+///             .status(404)
+///             .body(r#"{"type":"NotFound"}"#)
+///             .unwrap();
+///         Ok(response.into())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Transport: Send + Sync + 'static {
+    async fn execute(&self, request: Request) -> Result<Response>;
+}
+
+#[async_trait]
+impl Transport for reqwest::Client {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        Ok(reqwest::Client::execute(self, request).await?)
+    }
+}
+
+/// Lets the caller keep a handle to the transport, to inspect it later.
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn execute(&self, request: Request) -> Result<Response> {
+        T::execute(self, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use robespierre_models::id::ChannelId;
+
+    use crate::{
+        test_utils::{mock_http, MockTransport},
+        ApiError, HttpError,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_go_through_the_transport() {
+        let transport = Arc::new(MockTransport::default());
+        transport.respond(
+            200,
+            r#"{"_id":"01FE9XXP4NWJ6S2D4N7WX6W3AJ","nonce":"nonce","channel":"01FE9XVK2GXQXHZ5BCZBD6PA8P","author":"01FE9XNFEDH6NSPKRQY5SVXYRP","content":"hello"}"#,
+        );
+        transport.respond(
+            403,
+            r#"{"type":"MissingPermission","permission":"SendMessage"}"#,
+        );
+
+        let http = mock_http(&transport);
+        let channel: ChannelId = "01FE9XVK2GXQXHZ5BCZBD6PA8P".parse().unwrap();
+
+        let message = http
+            .send_message(channel, "hello", "nonce", vec![], vec![])
+            .await
+            .unwrap();
+        assert_eq!(message.channel, channel);
+
+        let err = http
+            .send_message(channel, "hello again", "nonce2", vec![], vec![])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            HttpError::Api {
+                error: ApiError::MissingPermission { .. },
+                ..
+            }
+        ));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let (method, url, body) = &requests[0];
+        assert_eq!(method, Method::POST);
+        assert_eq!(
+            url,
+            "https://revolt.test/channels/01FE9XVK2GXQXHZ5BCZBD6PA8P/messages"
+        );
+        let body: serde_json::Value = serde_json::from_str(body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["content"], "hello");
+        assert_eq!(body["nonce"], "nonce");
+    }
+}
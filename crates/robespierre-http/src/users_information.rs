@@ -13,7 +13,6 @@ impl Http {
             .get(ep!(self, "/users/@me"))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -25,7 +24,6 @@ impl Http {
             .get(ep!(self, "/users/{}" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -36,26 +34,7 @@ impl Http {
             .patch(ep!(self, "/users/@me"))
             .json(&patch)
             .send()
-            .await?
-            .error_for_status()?;
-
-        Ok(())
-    }
-
-    /// Edits an username
-    pub async fn edit_username(&self, username: &str, password: &str) -> Result {
-        #[derive(serde::Serialize)]
-        struct EditUsernameRequest<'a> {
-            username: &'a str,
-            password: &'a str,
-        }
-
-        self.client_user_session_auth_type()
-            .patch(ep!(self, "/users/@me/username"))
-            .json(&EditUsernameRequest { username, password })
-            .send()
-            .await?
-            .error_for_status()?;
+            .await?;
 
         Ok(())
     }
@@ -67,7 +46,6 @@ impl Http {
             .get(ep!(self, "/users/{}/profile" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
@@ -80,8 +58,26 @@ impl Http {
             .get(ep!(self, "/users/{}/mutual" user_id))
             .send()
             .await?
-            .error_for_status()?
             .json()
             .await?)
     }
 }
+
+impl UserHttp {
+    /// Edits an username
+    pub async fn edit_username(&self, username: &str, password: &str) -> Result {
+        #[derive(serde::Serialize)]
+        struct EditUsernameRequest<'a> {
+            username: &'a str,
+            password: &'a str,
+        }
+
+        self.client
+            .patch(ep!(self, "/users/@me/username"))
+            .json(&EditUsernameRequest { username, password })
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
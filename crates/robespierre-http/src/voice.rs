@@ -1 +1,22 @@
+use robespierre_models::id::ChannelId;
 
+use super::impl_prelude::*;
+
+impl Http {
+    /// Joins a voice channel, returning the token to authenticate on voso with
+    pub async fn join_call(&self, channel: ChannelId) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct JoinCallResponse {
+            token: String,
+        }
+
+        Ok(self
+            .client
+            .post(ep!(self, "/channels/{}/join_call" channel))
+            .send()
+            .await?
+            .json::<JoinCallResponse>()
+            .await?
+            .token)
+    }
+}
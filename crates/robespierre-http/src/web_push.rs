@@ -1 +1,29 @@
-// TODO web push
+use robespierre_models::sync::WebPushSubscription;
+
+use super::impl_prelude::*;
+
+impl UserHttp {
+    /// Subscribes the current session to web push notifications
+    pub async fn push_subscribe(&self, subscription: &WebPushSubscription) -> Result {
+        self.client
+            .post(ep!(self, "/push/subscribe"))
+            // subscribing again replaces the subscription of the session
+            .idempotent(true)
+            .json(subscription)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Unsubscribes the current session from web push notifications
+    pub async fn push_unsubscribe(&self) -> Result {
+        self.client
+            .post(ep!(self, "/push/unsubscribe"))
+            .idempotent(true)
+            .send()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 use std::str::FromStr;
-use std::{convert::Infallible, fmt};
+use std::{collections::HashMap, convert::Infallible, fmt};
 
 use serde::{Deserialize, Serialize};
 
+use crate::core::RevoltConfiguration;
+
 /*
 Newtypes
 */
@@ -84,7 +86,6 @@ impl fmt::Display for AttachmentId {
 /// Attachment metadata
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum AttachmentMetadata {
     File,
     Text,
@@ -129,7 +130,6 @@ impl fmt::Display for AttachmentTag {
 /// Attachment to a message, but can be any other media
 /// like avatars, server icons, channel icons, banners
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Attachment {
     #[serde(rename = "_id")]
     pub id: AttachmentId,
@@ -143,8 +143,7 @@ pub struct Attachment {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Autumn.ts#L47-L70
 
 /// File serving parameters
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct SizeOptions {
     /// Width of resized image
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -159,3 +158,135 @@ pub struct SizeOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_size: Option<u32>,
 }
+
+/// The configuration of autumn, returned from its root.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AutumnConfiguration {
+    /// The version of autumn.
+    pub autumn: String,
+    /// The configuration of each tag.
+    pub tags: HashMap<String, AutumnTagConfiguration>,
+}
+
+impl AutumnConfiguration {
+    /// Gets the configuration of a tag.
+    pub fn tag(&self, tag: AttachmentTag) -> Option<&AutumnTagConfiguration> {
+        self.tags.get(tag.to_str())
+    }
+}
+
+/// The configuration of a single tag in autumn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AutumnTagConfiguration {
+    /// The maximum size of the files uploaded with the tag, in bytes.
+    pub max_size: u64,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// The only kind of content accepted, like `Image`, if restricted.
+    #[serde(default)]
+    pub restrict_content_type: Option<String>,
+}
+
+impl SizeOptions {
+    /// The query string autumn expects, starting with `?`, or
+    /// an empty string if no options are set.
+    pub fn to_query(&self) -> String {
+        let params = [
+            ("width", self.width),
+            ("height", self.height),
+            ("size", self.size),
+            // autumn calls it max_side
+            ("max_side", self.max_size),
+        ];
+
+        let mut query = String::new();
+        for (name, value) in params.iter() {
+            if let Some(value) = value {
+                query.push(if query.is_empty() { '?' } else { '&' });
+                query.push_str(&format!("{}={}", name, value));
+            }
+        }
+        query
+    }
+}
+
+impl Attachment {
+    /// The url the attachment can be downloaded from.
+    ///
+    /// Works for any kind of attachment: message attachments, avatars,
+    /// icons, banners, and so on; `size` is used to resize images.
+    pub fn url(&self, config: &RevoltConfiguration, size: Option<&SizeOptions>) -> String {
+        format!(
+            "{}/{}/{}{}",
+            config.features.autumn.url(),
+            self.tag,
+            self.id,
+            size.map(SizeOptions::to_query).unwrap_or_default()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_size_queries() {
+        assert_eq!(SizeOptions::default().to_query(), "");
+
+        let size = SizeOptions {
+            width: Some(64),
+            ..Default::default()
+        };
+        assert_eq!(size.to_query(), "?width=64");
+
+        let size = SizeOptions {
+            width: Some(64),
+            height: Some(32),
+            size: None,
+            max_size: Some(128),
+        };
+        assert_eq!(size.to_query(), "?width=64&height=32&max_side=128");
+    }
+
+    #[test]
+    fn builds_attachment_urls() {
+        let config: RevoltConfiguration = serde_json::from_str(
+            r#"{
+                "revolt": "0.5.3",
+                "features": {
+                    "captcha": { "enabled": false, "key": "" },
+                    "email": false,
+                    "invite_only": false,
+                    "autumn": { "enabled": true, "url": "https://autumn.revolt.chat" },
+                    "january": { "enabled": true, "url": "https://jan.revolt.chat" },
+                    "voso": { "enabled": true, "url": "https://voso.revolt.chat", "ws": "wss://voso.revolt.chat/ws" }
+                },
+                "ws": "wss://ws.revolt.chat",
+                "app": "https://app.revolt.chat",
+                "vapid": ""
+            }"#,
+        )
+        .unwrap();
+        let avatar: Attachment = serde_json::from_str(
+            r#"{
+                "_id": "t6Bh1dVLp-7oLbYwMq0DQsMdNhUSWjk9ARq0iMhxsG",
+                "tag": "avatars",
+                "size": 1024,
+                "filename": "avatar.png",
+                "metadata": { "type": "Image", "width": 256, "height": 256 },
+                "content_type": "image/png"
+            }"#,
+        )
+        .unwrap();
+
+        let url = "https://autumn.revolt.chat/avatars/t6Bh1dVLp-7oLbYwMq0DQsMdNhUSWjk9ARq0iMhxsG";
+        assert_eq!(avatar.url(&config, None), url);
+
+        let size = SizeOptions {
+            size: Some(64),
+            ..Default::default()
+        };
+        assert_eq!(avatar.url(&config, Some(&size)), format!("{}?size=64", url));
+    }
+}
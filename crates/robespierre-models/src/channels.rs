@@ -21,7 +21,6 @@ Note: leave `channel_type`, and use that as the #[serde(tag=)] of `Channel`, but
 
 /// Saved Messages channel has only one participant, the user who created it.
 #[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct SavedMessagesChannel {
     #[serde(rename = "_id")]
     pub id: ChannelId,
@@ -34,7 +33,6 @@ pub struct SavedMessagesChannel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L43-L62
 
 #[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct DirectMessageChannel {
     #[serde(rename = "_id")]
     pub id: ChannelId,
@@ -51,7 +49,6 @@ pub struct DirectMessageChannel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L64-L108
 
 #[derive(Deserialize, Serialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct GroupChannel {
     #[serde(rename = "_id")]
     pub id: ChannelId,
@@ -87,7 +84,6 @@ pub struct GroupChannel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L110-L149
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ServerChannel {
     #[serde(rename = "_id")]
     pub id: ChannelId,
@@ -117,7 +113,6 @@ pub struct ServerChannel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L151-L155
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct TextChannel {
     #[serde(flatten)]
     pub server_channel: ServerChannel,
@@ -132,7 +127,6 @@ pub struct TextChannel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L157-L159
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct VoiceChannel {
     #[serde(flatten)]
     pub server_channel: ServerChannel,
@@ -147,7 +141,6 @@ pub struct VoiceChannel {
 /// A channel
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(tag = "channel_type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum Channel {
     SavedMessages(SavedMessagesChannel),
     DirectMessage(DirectMessageChannel),
@@ -159,7 +152,6 @@ pub enum Channel {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Channels.ts#L163-L210
 /// A message
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Message {
     #[serde(rename = "_id")]
     pub id: MessageId,
@@ -182,7 +174,6 @@ pub struct Message {
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[serde(untagged)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum MessageContent {
     Content(String),
     SystemMessage(SystemMessage),
@@ -190,7 +181,6 @@ pub enum MessageContent {
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[serde(tag = "type", rename_all = "snake_case")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum SystemMessage {
     Text { content: String },
     UserAdded { id: UserId, by: UserId },
@@ -210,7 +200,6 @@ Extra
 
 /// Data about what messages to reply to
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ReplyData {
     pub id: MessageId,
     pub mention: bool,
@@ -276,7 +265,6 @@ pub enum ChannelType {
 /// A channel where all the fields are optional, and can be treated as a patch that
 /// can be applied to a [`Channel`].
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct PartialChannel {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     user: Option<UserId>,
@@ -477,7 +465,6 @@ impl PartialChannel {
 /// A message where all the fields are optional, and can be treated as a patch
 /// that can be applied to a [`Message`].
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct PartialMessage {
     #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
     pub id: Option<MessageId>,
@@ -580,7 +567,6 @@ impl From<WrappedDate> for Date {
 }
 
 #[derive(Serialize, Deserialize)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 struct WrappedDate {
     #[serde(rename = "$date")]
     date: DateTime<Utc>,
@@ -650,7 +636,6 @@ impl Display for ChannelInviteCode {
 }
 
 #[derive(Deserialize, Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct CreateChannelInviteResponse {
     code: ChannelInviteCode,
 }
@@ -672,7 +657,7 @@ pub struct MessageFilter {
 }
 
 /// THe direction of a message filter
-#[derive(Debug, Serialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
 pub enum MessageFilterSortDirection {
     /// Tkae the latest messages first.
     Latest,
@@ -686,6 +671,37 @@ impl Default for MessageFilterSortDirection {
     }
 }
 
+/// Options when searching for messages
+#[derive(Debug, Default, Serialize)]
+pub struct MessageSearchOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<MessageId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<MessageId>,
+    pub sort: MessageSearchSortDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_users: Option<bool>,
+}
+
+/// The order of the results of a message search
+#[derive(Debug, Serialize)]
+pub enum MessageSearchSortDirection {
+    /// Take the messages that match the query best first.
+    Relevance,
+    /// Take the latest messages first.
+    Latest,
+    /// Take the oldest messages first.
+    Oldest,
+}
+
+impl Default for MessageSearchSortDirection {
+    fn default() -> Self {
+        Self::Relevance
+    }
+}
+
 /// Server channel type
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum ServerChannelType {
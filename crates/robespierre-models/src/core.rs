@@ -1,7 +1,6 @@
 /// Data about a revolt instance obtained by
 /// making a `GET /` on the api.
-#[derive(serde::Deserialize, Clone)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct RevoltConfiguration {
     pub revolt: String,
     pub features: RevoltInstanceFeatures,
@@ -11,9 +10,8 @@ pub struct RevoltConfiguration {
 }
 
 /// Data about Autumn (file server microservice).
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[serde(transparent)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Autumn(EnabledUrl);
 
 impl Autumn {
@@ -29,9 +27,8 @@ impl Autumn {
 }
 
 /// Data about January (image proxy and embed generator).
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[serde(transparent)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct January(EnabledUrl);
 
 impl January {
@@ -47,9 +44,8 @@ impl January {
 }
 
 /// Data about Voso (legacy voice server).
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 #[serde(transparent)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Voso(EnabledUrlWs);
 
 impl Voso {
@@ -69,15 +65,13 @@ impl Voso {
     }
 }
 
-#[derive(serde::Deserialize, Clone)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 struct EnabledUrl {
     enabled: bool,
     url: String,
 }
 
-#[derive(serde::Deserialize, Clone)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 struct EnabledUrlWs {
     enabled: bool,
     url: String,
@@ -86,8 +80,7 @@ struct EnabledUrlWs {
 
 /// Features
 
-#[derive(serde::Deserialize, Clone)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct RevoltInstanceFeatures {
     // pub registration: bool,
     pub captcha: CaptchaInfo,
@@ -104,8 +97,7 @@ pub struct RevoltInstanceFeatures {
 }
 
 /// Captcha feature
-#[derive(serde::Deserialize, Clone)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct CaptchaInfo {
     /// Whether it is enabled or not
     pub enabled: bool,
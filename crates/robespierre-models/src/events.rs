@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{
     channels::{Channel, ChannelField, Message, PartialChannel, PartialMessage},
@@ -21,8 +22,7 @@ pub enum ClientToServerEvent {
 }
 
 /// Event received after authentication.
-#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 pub struct ReadyEvent {
     pub users: Vec<User>,
     pub servers: Vec<Server>,
@@ -31,9 +31,12 @@ pub struct ReadyEvent {
 }
 
 /// Any message that the server can send to the client.
-#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+///
+/// Use [`ServerToClientEvent::from_json`] to parse events, so that the ones
+/// this version of the library doesn't know about end up in
+/// [`ServerToClientEvent::Unknown`], instead of failing.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum ServerToClientEvent {
     Error {
         error: String,
@@ -138,8 +141,191 @@ pub enum ServerToClientEvent {
         user: UserId,
         status: RelationshipStatus,
     },
+    /// An event that doesn't match any of the other variants, either
+    /// because its type is new or because its data changed.
+    #[serde(skip)]
+    Unknown {
+        /// The `type` of the event.
+        kind: String,
+        /// The whole event, as received.
+        raw: Value,
+    },
+}
+
+impl ServerToClientEvent {
+    /// Parses an event from the json received from the server.
+    ///
+    /// Events that don't match any of the known variants are returned as
+    /// [`ServerToClientEvent::Unknown`], and fields the models don't know about
+    /// are ignored, unless `strict`, in which case both are errors.
+    pub fn from_json(json: &str, strict: bool) -> serde_json::Result<Self> {
+        if !strict {
+            // most events are known, so parse them directly
+            // before falling back to the intermediate value
+            if let Ok(event) = serde_json::from_str(json) {
+                return Ok(event);
+            }
+        }
+
+        Self::from_value(serde_json::from_str(json)?, strict)
+    }
+
+    /// Like [`ServerToClientEvent::from_json`], but for an event that was already
+    /// parsed, or decoded from another format.
+    pub fn from_value(raw: Value, strict: bool) -> serde_json::Result<Self> {
+        let kind = raw
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let event = match Self::deserialize(&raw) {
+            Ok(event) => event,
+            Err(e) if strict => return Err(e),
+            Err(e) => {
+                // new types of events are expected, but a known one
+                // failing to parse means its data changed
+                if is_known_kind(&kind) {
+                    tracing::warn!("Cannot parse {} event ({}), passing it as unknown", kind, e);
+                }
+                return Ok(Self::Unknown { kind, raw });
+            }
+        };
+
+        if strict {
+            // serde can only deny unknown fields at compile time, so instead look for
+            // the fields of the raw event that don't make it back when serializing it
+            let mut unknown = vec![];
+            unknown_fields(&raw, &serde_json::to_value(&event)?, "", &mut unknown);
+            if !unknown.is_empty() {
+                return Err(serde_json::Error::custom(format!(
+                    "unknown fields in {} event: {}",
+                    kind,
+                    unknown.join(", ")
+                )));
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+/// The types of the known events, without their data;
+/// keep in sync with [`ServerToClientEvent`].
+#[derive(Deserialize)]
+enum KnownKind {
+    Error,
+    Authenticated,
+    Pong,
+    Ready,
+    Message,
+    MessageUpdate,
+    MessageDelete,
+    ChannelCreate,
+    ChannelUpdate,
+    ChannelDelete,
+    ChannelGroupJoin,
+    ChannelGroupLeave,
+    ChannelStartTyping,
+    ChannelStopTyping,
+    ChannelAck,
+    ServerUpdate,
+    ServerDelete,
+    ServerMemberUpdate,
+    ServerMemberJoin,
+    ServerMemberLeave,
+    ServerRoleUpdate,
+    ServerRoleDelete,
+    UserUpdate,
+    UserRelationship,
+}
+
+fn is_known_kind(kind: &str) -> bool {
+    KnownKind::deserialize(Value::from(kind)).is_ok()
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(array) => array.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+/// Collects the paths of the fields in `raw` that are missing from `known`.
+fn unknown_fields(raw: &Value, known: &Value, path: &str, unknown: &mut Vec<String>) {
+    match (raw, known) {
+        (Value::Object(raw), Value::Object(known)) => {
+            for (key, value) in raw {
+                let path = format!("{}.{}", path, key);
+                match known.get(key) {
+                    Some(known) => unknown_fields(value, known, &path, unknown),
+                    // optional fields are skipped when serializing if they are empty
+                    None if is_empty(value) => {}
+                    None => unknown.push(path[1..].to_string()),
+                }
+            }
+        }
+        (Value::Array(raw), Value::Array(known)) => {
+            for (i, (raw, known)) in raw.iter().zip(known).enumerate() {
+                unknown_fields(raw, known, &format!("{}[{}]", path, i), unknown);
+            }
+        }
+        _ => {}
+    }
 }
 
 pub trait HasWsUrl {
     fn get_ws_url(&self) -> &str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_unknown_events() {
+        let json = r#"{"type":"ChannelStartTyping","id":"01FE9XVK2GXQXHZ5BCZBD6PA8P","user":"01FE9XNFEDH6NSPKRQY5SVXYRP"}"#;
+        assert!(matches!(
+            ServerToClientEvent::from_json(json, true).unwrap(),
+            ServerToClientEvent::ChannelStartTyping { .. }
+        ));
+
+        let json = r#"{"type":"EmojiCreate","id":"01FE9XVK2GXQXHZ5BCZBD6PA8P"}"#;
+        match ServerToClientEvent::from_json(json, false).unwrap() {
+            ServerToClientEvent::Unknown { kind, raw } => {
+                assert_eq!(kind, "EmojiCreate");
+                assert_eq!(raw["id"], "01FE9XVK2GXQXHZ5BCZBD6PA8P");
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+        assert!(ServerToClientEvent::from_json(json, true).is_err());
+    }
+
+    #[test]
+    fn strict_denies_unknown_fields() {
+        let json = r#"{"type":"ChannelStartTyping","id":"01FE9XVK2GXQXHZ5BCZBD6PA8P","user":"01FE9XNFEDH6NSPKRQY5SVXYRP","since":0}"#;
+        assert!(matches!(
+            ServerToClientEvent::from_json(json, false).unwrap(),
+            ServerToClientEvent::ChannelStartTyping { .. }
+        ));
+
+        let error = ServerToClientEvent::from_json(json, true).unwrap_err();
+        assert!(error.to_string().contains("since"), "{}", error);
+    }
+
+    #[test]
+    fn tells_unknown_events_from_changed_ones() {
+        assert!(!is_known_kind("EmojiCreate"));
+        assert!(!is_known_kind("Unknown"));
+        assert!(!is_known_kind(""));
+        assert!(is_known_kind("ChannelStartTyping"));
+
+        let json = r#"{"type":"ChannelStartTyping","id":"01FE9XVK2GXQXHZ5BCZBD6PA8P"}"#;
+        match ServerToClientEvent::from_json(json, false).unwrap() {
+            ServerToClientEvent::Unknown { kind, .. } => assert!(is_known_kind(&kind)),
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+}
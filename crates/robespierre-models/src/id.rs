@@ -22,8 +22,7 @@ impl IdString {
             });
         }
 
-        if let Some(pos) = s.find(|c: char| !('A'..='Z').contains(&c) && !('0'..='9').contains(&c))
-        {
+        if let Some(pos) = s.find(|c: char| !c.is_ascii_uppercase() && !c.is_ascii_digit()) {
             let c = s.chars().nth(pos).unwrap();
 
             return Err(IdStringDeserializeError::InvalidCharacter { c, pos });
@@ -168,9 +167,7 @@ impl VarLenIdString {
             });
         }
 
-        if let Some(pos) = s.find(|c: char| {
-            !('A'..='Z').contains(&c) && !('a'..='z').contains(&c) && !('0'..='9').contains(&c)
-        }) {
+        if let Some(pos) = s.find(|c: char| !c.is_ascii_alphanumeric()) {
             let c = s.chars().nth(pos).unwrap();
 
             return Err(VarLenIdStringDeserializeError::InvalidCharacter { c, pos });
@@ -446,7 +443,6 @@ id_impl! {@dt InviteId, IdString}
 ///
 /// Note: it is a pair of a [`ServerId`] and [`UserId`]
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct MemberId {
     pub server: ServerId,
     pub user: UserId,
@@ -8,7 +8,6 @@ use crate::{
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Invites.ts#L4-L26
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ServerInvite {
     #[serde(rename = "_id")]
     pub id: InviteId,
@@ -22,7 +21,6 @@ pub struct ServerInvite {
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum Invite {
     Invite(ServerInvite),
 }
@@ -30,7 +28,6 @@ pub enum Invite {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Invites.ts#L30-L42
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ServerRetrievedInvite {
     pub server_id: ServerId,
     pub server_name: String,
@@ -50,7 +47,6 @@ pub struct ServerRetrievedInvite {
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum RetrievedInvite {
     Server(ServerRetrievedInvite),
 }
@@ -8,7 +8,6 @@ Types
 
 /// Embedded image
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct EmbedImage {
     pub url: String,
     pub width: u32,
@@ -20,7 +19,6 @@ pub struct EmbedImage {
 
 /// Embedded video
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct EmbedVideo {
     pub url: String,
     pub width: u32,
@@ -32,7 +30,6 @@ pub struct EmbedVideo {
 /// Data about an embed of a special website, if it is the case
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum EmbedSpecial {
     None,
     YouTube {
@@ -79,7 +76,6 @@ pub enum SizeType {
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(tag = "type")]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub enum Embed {
     None,
     Website {
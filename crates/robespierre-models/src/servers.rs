@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    autumn::Attachment,
+    autumn::{Attachment, AttachmentId},
     channels::ChannelPermissions,
     id::{CategoryId, ChannelId, MemberId, RoleId, ServerId, UserId},
 };
@@ -19,7 +19,6 @@ pub type MemberCompositeKey = MemberId;
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L10-L18
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Member {
     #[serde(rename = "_id")]
     pub id: MemberCompositeKey,
@@ -34,7 +33,6 @@ pub struct Member {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L20-L23
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Ban {
     #[serde(rename = "_id")]
     pub id: MemberCompositeKey,
@@ -71,7 +69,6 @@ pub type Color = String;
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L48-L71
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Role {
     /// The name of the role.
     pub name: String,
@@ -102,7 +99,6 @@ pub struct Role {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L73
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct RoleInformation {
     pub name: String,
     #[serde(rename = "colour", default, skip_serializing_if = "Option::is_none")]
@@ -116,7 +112,6 @@ pub struct RoleInformation {
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L75-L81
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Category {
     pub id: CategoryId,
     pub title: String,
@@ -125,8 +120,7 @@ pub struct Category {
 
 // https://github.com/revoltchat/api/blob/097f40e37108cd3a1816b1c2cc69a137ae317069/types/Servers.ts#L83-L92
 
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct SystemMessageChannels {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub user_joined: Option<ChannelId>,
@@ -142,7 +136,6 @@ pub struct SystemMessageChannels {
 
 /// A server.
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct Server {
     #[serde(rename = "_id")]
     pub id: ServerId,
@@ -188,7 +181,6 @@ Extra
 /// A member where all the fields are optional, and can be treated as
 /// a patch that can be applied to a [`Member`].
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct PartialMember {
     #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
     pub id: Option<MemberId>,
@@ -255,7 +247,7 @@ impl MemberField {
 pub struct RolesObject(HashMap<RoleId, Role>);
 
 impl RolesObject {
-    pub fn iter(&self) -> RolesIter {
+    pub fn iter(&self) -> RolesIter<'_> {
         RolesIter(self.0.iter())
     }
 
@@ -291,7 +283,6 @@ impl<'a> Iterator for RolesIter<'a> {
 /// A role where all the fields are optional, and can be used to
 /// describe a patch applied to a role.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct PartialRole {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -357,10 +348,25 @@ impl RoleField {
     }
 }
 
+/// A patch to a role.
+#[derive(Serialize, Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub struct RoleEditPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "colour", skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hoist: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<RoleField>,
+}
+
 /// A server where all the fields are optional, and so can be
 /// treated as a patch that can be applied to a [`Server`].
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct PartialServer {
     #[serde(rename = "_id", default, skip_serializing_if = "Option::is_none")]
     pub id: Option<ServerId>,
@@ -473,6 +479,28 @@ impl PartialServer {
     }
 }
 
+/// A patch to a server.
+#[derive(Serialize, Default, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[non_exhaustive]
+pub struct ServerEditPatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<AttachmentId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banner: Option<AttachmentId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<Category>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_messages: Option<SystemMessageChannels>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<ServerField>,
+}
+
 /// A server field, that can be used to unset a field in a [`Server`].
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum ServerField {
@@ -17,7 +17,6 @@ pub struct UserSettings(pub HashMap<String, SettingTuple>);
 // https://github.com/revoltchat/api/blob/094f8e650dbbbfd6a61be60d20943ea471a816c6/types/Sync.ts#L7-L10
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ChannelCompositeKey {
     pub channel: ChannelId,
     pub user: UserId,
@@ -26,7 +25,6 @@ pub struct ChannelCompositeKey {
 // https://github.com/revoltchat/api/blob/094f8e650dbbbfd6a61be60d20943ea471a816c6/types/Sync.ts#L12-L17
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct ChannelUnread {
     #[serde(rename = "_id")]
     pub id: ChannelCompositeKey,
@@ -38,13 +36,28 @@ pub struct ChannelUnread {
 // https://github.com/revoltchat/api/blob/094f8e650dbbbfd6a61be60d20943ea471a816c6/types/Sync.ts#L19-L23
 
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
-#[cfg_attr(debug_assertions, serde(deny_unknown_fields))]
 pub struct WebPushSubscription {
     pub endpoint: String,
     pub p256dh: String,
     pub auth: String,
 }
 
+impl WebPushSubscription {
+    /// Creates a subscription from the values of a `PushSubscription`: the
+    /// endpoint, and the base64 encoded `p256dh` and `auth` keys.
+    pub fn new(
+        endpoint: impl Into<String>,
+        p256dh: impl Into<String>,
+        auth: impl Into<String>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            p256dh: p256dh.into(),
+            auth: auth.into(),
+        }
+    }
+}
+
 /*
 Extra
 */
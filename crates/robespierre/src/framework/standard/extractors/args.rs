@@ -242,7 +242,7 @@ impl Arg for UserOptMember {
     const TRIM: bool = <User as Arg>::TRIM;
 
     fn parse_arg(ctx: &FwContext, msg: &Msg, s: &str) -> Self::Fut {
-        let user_fut = User::parse_arg(&ctx, msg, s);
+        let user_fut = User::parse_arg(ctx, msg, s);
 
         let ctx = ctx.clone();
         let ch = msg.message.channel;
@@ -644,7 +644,7 @@ impl<'a> Iterator for ArgsLexer<'a> {
 
         self.last_arg_indices = Some((prev_pos, self.current_pos));
 
-        return Some(Argument::Simple(&self.args[prev_pos..]));
+        Some(Argument::Simple(&self.args[prev_pos..]))
     }
 }
 
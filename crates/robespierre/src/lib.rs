@@ -143,6 +143,19 @@ pub trait EventHandler: Send + Sync {
         status: RelationshipStatus,
     ) {
     }
+
+    /// Called for events that this version of the library doesn't know about,
+    /// with the `type` of the event and the whole event.
+    ///
+    /// Known events whose data changed, and so can't be parsed anymore, also end up
+    /// here (with a warning) instead of in their own handler: a message that fails
+    /// to parse is passed to this, not to [`EventHandler::on_message`]. Connect with
+    /// `Connection::strict` to get an error for them instead.
+    async fn on_unknown_event(&self, ctx: Context, kind: String, raw: serde_json::Value) {}
+
+    /// Called when the connection was lost and reestablished, when running with
+    /// [`robespierre_events::Connection::run_with_reconnect`].
+    async fn on_reconnect(&self, ctx: Context) {}
 }
 
 /// Wraps an event handler, updating the cache and then forwarding the events
@@ -180,6 +193,13 @@ where
 
         self.0.handle(ctx, event).await
     }
+
+    async fn handle_reconnect(self, ctx: Self::Context)
+    where
+        Self::Context: Send,
+    {
+        self.0.handle_reconnect(ctx).await
+    }
 }
 
 #[cfg(all(feature = "events", feature = "framework"))]
@@ -358,6 +378,14 @@ impl<
             .on_user_relationship_update(ctx, self_id, other_id, status)
             .await
     }
+
+    async fn on_unknown_event(&self, ctx: Context, kind: String, raw: serde_json::Value) {
+        self.inner.on_unknown_event(ctx, kind, raw).await
+    }
+
+    async fn on_reconnect(&self, ctx: Context) {
+        self.inner.on_reconnect(ctx).await
+    }
 }
 
 /// "Maintains" the list of servers in the cache, keeping it the
@@ -401,15 +429,11 @@ where
     async fn handle(self, ctx: Self::Context, event: ServerToClientEvent) {
         if let Some(cache) = ctx.cache() {
             match &event {
-                ServerToClientEvent::ServerMemberJoin { id, user } => {
-                    if *user == self.user_id {
-                        let _ = id.server(&ctx).await; // will fetch server and store to cache
-                    }
+                ServerToClientEvent::ServerMemberJoin { id, user } if *user == self.user_id => {
+                    let _ = id.server(&ctx).await; // will fetch server and store to cache
                 }
-                ServerToClientEvent::ServerMemberLeave { id, user } => {
-                    if *user == self.user_id {
-                        cache.delete_server(*id).await;
-                    }
+                ServerToClientEvent::ServerMemberLeave { id, user } if *user == self.user_id => {
+                    cache.delete_server(*id).await;
                 }
                 _ => {}
             }
@@ -417,6 +441,13 @@ where
 
         self.inner.handle(ctx, event).await
     }
+
+    async fn handle_reconnect(self, ctx: Self::Context)
+    where
+        Self::Context: Send,
+    {
+        self.inner.handle_reconnect(ctx).await
+    }
 }
 
 /// An object that can be passed to [`robespierre_events::Connection::run`], and
@@ -513,8 +544,15 @@ where
                     .on_user_relationship_update(ctx, id, user, status)
                     .await
             }
+            ServerToClientEvent::Unknown { kind, raw } => {
+                self.0.on_unknown_event(ctx, kind, raw).await
+            }
         }
     }
+
+    async fn handle_reconnect(self, ctx: Self::Context) {
+        self.0.on_reconnect(ctx).await
+    }
 }
 
 #[derive(Clone)]
@@ -580,6 +618,15 @@ impl Context {
 
         TypingSession::new(channel, messanger.clone())
     }
+
+    /// The round-trip time to the websocket, measured with the last ping,
+    /// or `None` if not known yet.
+    ///
+    /// See [`robespierre_events::Connection::latency`].
+    #[cfg(feature = "events")]
+    pub fn latency(&self) -> Option<std::time::Duration> {
+        self.messanger.as_ref()?.latency()
+    }
 }
 
 #[cfg(feature = "events")]
@@ -601,7 +648,7 @@ impl HasCache for Context {
 
 impl HasHttp for Context {
     fn get_http(&self) -> &Http {
-        &*self.http
+        &self.http
     }
 }
 
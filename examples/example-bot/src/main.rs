@@ -27,7 +27,7 @@ use robespierre::{
     Authentication, CacheHttp, CacheServersMaintainer, CacheWrap, FrameworkWrap, UserData,
 };
 use robespierre_cache::CacheConfig;
-use robespierre_events::Connection;
+use robespierre_events::{Connection, ReconnectPolicy};
 use robespierre_http::Http;
 use robespierre_models::autumn::AttachmentTag;
 use robespierre_models::channels::{Channel, Message, MessageContent, ReplyData};
@@ -94,7 +94,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let handler = CacheWrap::new(handler);
 
-    connection.run(ctx, handler).await?;
+    connection
+        .run_with_reconnect(ctx, handler, ReconnectPolicy::default())
+        .await?;
 
     Ok(())
 }
@@ -123,6 +125,11 @@ async fn ping(ctx: &FwContext, message: &Message) -> CommandResult {
     message
         .reply(ctx, format!("I got {} pings since I came online", num))
         .await?;
+    if let Some(latency) = ctx.as_ref().latency() {
+        message
+            .reply(ctx, format!("Gateway latency is {:?}", latency))
+            .await?;
+    }
 
     Ok(())
 }
@@ -29,7 +29,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let cache = Cache::new(CacheConfig::default());
 
-    let acc = http.fetch_account().await?;
+    let acc = http.fetch_self().await?;
 
     loop {
         let event = connection.next().await?;
@@ -38,15 +38,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // maintain the server list
         match &event {
-            ServerToClientEvent::ServerMemberJoin { id, user } => {
-                if *user == acc.id {
-                    http.fetch_server(*id).await?.commit_to_cache(&cache).await;
-                }
+            ServerToClientEvent::ServerMemberJoin { id, user } if *user == acc.id => {
+                http.fetch_server(*id).await?.commit_to_cache(&cache).await;
             }
-            ServerToClientEvent::ServerMemberLeave { id, user } => {
-                if *user == acc.id {
-                    cache.delete_server(*id).await;
-                }
+            ServerToClientEvent::ServerMemberLeave { id, user } if *user == acc.id => {
+                cache.delete_server(*id).await;
             }
             _ => {}
         }
@@ -28,7 +28,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let http = Http::new(&auth).await?;
     let connection = Connection::connect(&auth).await?;
 
-    let acc = http.fetch_account().await?;
+    let acc = http.fetch_self().await?;
 
     let cache = Cache::new(CacheConfig::default());
 
@@ -77,38 +77,37 @@ impl RawEventHandler for Handler {
         event.commit_to_cache_ref(&ctx).await;
 
         if let ServerToClientEvent::Message { message } = event {
-            match &message.content {
-                MessageContent::SystemMessage(SystemMessage::UserJoined { id }) => {
-                    if *id == ctx.3 {
-                        // don't welcome yourself
-                        return;
-                    }
-
-                    if message.channel != "01FDFCXPJ92A3MJ01DDVFYWR8M" {
-                        return;
-                    }
-
-                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-
-                    ctx.2
-                        .as_ref()
-                        .unwrap()
-                        .send(ConnectionMessage::StartTyping {
-                            channel: message.channel,
-                        });
-
-                    tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-
-                    let _ = message
-                        .channel
-                        .send_message(&ctx, |m| m.content("Welcome"))
-                        .await;
-
-                    ctx.2.as_ref().unwrap().send(ConnectionMessage::StopTyping {
+            if let MessageContent::SystemMessage(SystemMessage::UserJoined { id }) =
+                &message.content
+            {
+                if *id == ctx.3 {
+                    // don't welcome yourself
+                    return;
+                }
+
+                if message.channel != "01FDFCXPJ92A3MJ01DDVFYWR8M" {
+                    return;
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+
+                ctx.2
+                    .as_ref()
+                    .unwrap()
+                    .send(ConnectionMessage::StartTyping {
                         channel: message.channel,
                     });
-                }
-                _ => {}
+
+                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+
+                let _ = message
+                    .channel
+                    .send_message(&ctx, |m| m.content("Welcome"))
+                    .await;
+
+                ctx.2.as_ref().unwrap().send(ConnectionMessage::StopTyping {
+                    channel: message.channel,
+                });
             }
         }
     }
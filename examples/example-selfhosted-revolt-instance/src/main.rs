@@ -28,7 +28,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // call Http::new_with_url and Connection::connect_with_url instead of Http::new and Connection::connect
     let http = Http::new_with_url(&auth, API_ROOT_URL).await?;
-    let connection = Connection::connect_with_url(&auth, http.get_ws_url()).await?;
+    let connection = Connection::connect_with_url(&auth, &http.get_ws_url().await?).await?;
 
     let context = Context::new(http, robespierre::typemap::ShareMap::custom())
         .with_cache(CacheConfig::default());